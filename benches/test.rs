@@ -1,6 +1,7 @@
 use criterion::*;
 use dijkstra::dijkstra::*;
 use dijkstra::graph::*;
+use dijkstra::pairing_heap::*;
 use std::time::Duration;
 
 fn sparse_instance() -> GraphNetwork<DijkstraNode, DijkstraArc> {
@@ -43,27 +44,27 @@ fn bench_simple_dijkstra(c: &mut Criterion) {
     group.measurement_time(Duration::from_secs(30));
     group.bench_function("sparse", |b| {
         b.iter_batched(
-            || sparse_instance(),
+            sparse_instance,
             |mut network| {
-                simple_dijkstra(&mut network, 0);
+                simple_dijkstra(&mut network, 0).unwrap();
             },
             BatchSize::LargeInput,
         );
     });
     group.bench_function("dense", |b| {
         b.iter_batched(
-            || dense_instance(),
+            dense_instance,
             |mut network| {
-                simple_dijkstra(&mut network, 0);
+                simple_dijkstra(&mut network, 0).unwrap();
             },
             BatchSize::LargeInput,
         );
     });
     group.bench_function("mini", |b| {
         b.iter_batched(
-            || mini_instance(),
+            mini_instance,
             |mut network| {
-                simple_dijkstra(&mut network, 0);
+                simple_dijkstra(&mut network, 0).unwrap();
             },
             BatchSize::LargeInput,
         );
@@ -75,32 +76,160 @@ fn bench_dijkstra(c: &mut Criterion) {
     group.measurement_time(Duration::from_secs(30));
     group.bench_function("sparse", |b| {
         b.iter_batched(
-            || sparse_instance(),
+            sparse_instance,
             |mut network| {
-                dijkstra(&mut network, 0);
+                dijkstra(&mut network, 0).unwrap();
             },
             BatchSize::LargeInput,
         );
     });
     group.bench_function("dense", |b| {
         b.iter_batched(
-            || dense_instance(),
+            dense_instance,
             |mut network| {
-                dijkstra(&mut network, 0);
+                dijkstra(&mut network, 0).unwrap();
             },
             BatchSize::LargeInput,
         );
     });
     group.bench_function("mini", |b| {
         b.iter_batched(
-            || mini_instance(),
+            mini_instance,
             |mut network| {
-                dijkstra(&mut network, 0);
+                dijkstra(&mut network, 0).unwrap();
             },
             BatchSize::LargeInput,
         );
     });
 }
 
-criterion_group!(benches, bench_simple_dijkstra, bench_dijkstra);
+fn bench_dijkstra_pairing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pairing");
+    group.measurement_time(Duration::from_secs(30));
+    group.bench_function("sparse", |b| {
+        b.iter_batched(
+            sparse_instance,
+            |mut network| {
+                dijkstra_with_heap(&mut network, 0, PairingHeap::new()).unwrap();
+            },
+            BatchSize::LargeInput,
+        );
+    });
+    group.bench_function("dense", |b| {
+        b.iter_batched(
+            dense_instance,
+            |mut network| {
+                dijkstra_with_heap(&mut network, 0, PairingHeap::new()).unwrap();
+            },
+            BatchSize::LargeInput,
+        );
+    });
+    group.bench_function("mini", |b| {
+        b.iter_batched(
+            mini_instance,
+            |mut network| {
+                dijkstra_with_heap(&mut network, 0, PairingHeap::new()).unwrap();
+            },
+            BatchSize::LargeInput,
+        );
+    });
+}
+
+fn bench_dijkstra_lazy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("binary");
+    group.measurement_time(Duration::from_secs(30));
+    group.bench_function("sparse", |b| {
+        b.iter_batched(
+            sparse_instance,
+            |mut network| {
+                dijkstra_lazy(&mut network, 0).unwrap();
+            },
+            BatchSize::LargeInput,
+        );
+    });
+    group.bench_function("dense", |b| {
+        b.iter_batched(
+            dense_instance,
+            |mut network| {
+                dijkstra_lazy(&mut network, 0).unwrap();
+            },
+            BatchSize::LargeInput,
+        );
+    });
+    group.bench_function("mini", |b| {
+        b.iter_batched(
+            mini_instance,
+            |mut network| {
+                dijkstra_lazy(&mut network, 0).unwrap();
+            },
+            BatchSize::LargeInput,
+        );
+    });
+}
+
+fn bench_construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("construction");
+    group.measurement_time(Duration::from_secs(30));
+    group.bench_function("sparse", |b| b.iter(sparse_instance));
+    group.bench_function("dense", |b| b.iter(dense_instance));
+}
+
+/// visiting every arc with its data, by `from_node` + `data_of_arc` and by `edges_of_node`
+fn bench_adjacency(c: &mut Criterion) {
+    let mut group = c.benchmark_group("adjacency");
+    group.measurement_time(Duration::from_secs(30));
+    let network = sparse_instance();
+    group.bench_function("from_node", |b| {
+        b.iter(|| {
+            (0..network.node_count())
+                .flat_map(|node_id| network.from_node(node_id))
+                .map(|(_, arc_id)| network.data_of_arc(arc_id).unwrap().weight())
+                .sum::<usize>()
+        });
+    });
+    group.bench_function("edges_of_node", |b| {
+        b.iter(|| {
+            (0..network.node_count())
+                .flat_map(|node_id| network.edges_of_node(node_id))
+                .map(|(_, arc)| arc.weight())
+                .sum::<usize>()
+        });
+    });
+}
+
+/// many single source runs, one after another and in parallel
+#[cfg(feature = "rayon")]
+fn bench_parallel_all_sources(c: &mut Criterion) {
+    let mut group = c.benchmark_group("all_sources");
+    group.measurement_time(Duration::from_secs(30));
+    let network = sparse_instance();
+    let sources: Vec<usize> = (0..64).collect();
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            sources
+                .iter()
+                .map(|&source| shortest_distances(&network, source).unwrap())
+                .collect::<Vec<_>>()
+        });
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| parallel_all_sources(&network, &sources).unwrap());
+    });
+}
+
+#[cfg(feature = "rayon")]
+criterion_group!(parallel_benches, bench_parallel_all_sources);
+
+criterion_group!(
+    benches,
+    bench_simple_dijkstra,
+    bench_dijkstra,
+    bench_dijkstra_pairing,
+    bench_dijkstra_lazy,
+    bench_construction,
+    bench_adjacency
+);
+#[cfg(not(feature = "rayon"))]
 criterion_main!(benches);
+#[cfg(feature = "rayon")]
+criterion_main!(benches, parallel_benches);
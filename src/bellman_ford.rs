@@ -0,0 +1,166 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::dijkstra::*;
+use crate::graph::*;
+use crate::weight::*;
+
+/// a cycle of negative total weight is reachable from the start node, so shortest paths are not defined.
+/// `node` is on the cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativeCycle {
+    pub node: NodeId,
+}
+impl Display for NegativeCycle {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "node {} is on a negative cycle", self.node)
+    }
+}
+impl std::error::Error for NegativeCycle {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BellmanFordError {
+    /// the start node is out of range or has been removed
+    InvalidStart(NodeId),
+    NegativeCycle(NegativeCycle),
+}
+impl Display for BellmanFordError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            BellmanFordError::InvalidStart(node) => write!(f, "start node {} does not exist", node),
+            BellmanFordError::NegativeCycle(error) => write!(f, "{}", error),
+        }
+    }
+}
+impl std::error::Error for BellmanFordError {}
+impl From<DijkstraError> for BellmanFordError {
+    fn from(error: DijkstraError) -> Self {
+        match error {
+            DijkstraError::InvalidStart(node) => BellmanFordError::InvalidStart(node),
+        }
+    }
+}
+
+/// relax every arc once. returns the node relaxed last, if any.
+fn relax_all<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    arcs: &[(ArcId, NodeId, NodeId, W)],
+) -> Option<NodeId> {
+    let mut last_relaxed = None;
+    for &(arc_id, from, into, weight) in arcs.iter() {
+        let Some(from_distance) = network.data_of_node(from).unwrap().distance.finite() else {
+            // not reached yet
+            continue;
+        };
+        let Some(new_distance) = from_distance.checked_add(weight) else {
+            // overflows
+            continue;
+        };
+        let node = network.mut_data_of_node(into).unwrap();
+        if new_distance < node.distance {
+            node.distance = new_distance;
+            node.predecessor = Some(from);
+            node.predecessor_arc = Some(arc_id);
+            last_relaxed = Some(into);
+        }
+    }
+    last_relaxed
+}
+
+/// single source shortest paths which allows negative weights. O(V E).
+/// fills `distance`, `predecessor` and `predecessor_arc` of the nodes in the same way as `dijkstra`.
+/// if a negative cycle is reachable from the start node, the distances are left incomplete and an error is returned.
+/// returns an error without touching distances if the start node does not exist.
+pub fn bellman_ford<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+) -> std::result::Result<(), BellmanFordError> {
+    check_start(network, start_node_id)?;
+    reset_distances(network);
+    // set start node distance to 0
+    network.mut_data_of_node(start_node_id).unwrap().distance = W::ZERO;
+
+    let mut arcs: Vec<(ArcId, NodeId, NodeId, W)> = Vec::new();
+    for from in 0..network.node_data.len() {
+        if network.is_node_in(from) {
+            for (into, arc_id) in network.from_node(from) {
+                arcs.push((arc_id, from, into, network.data_of_arc(arc_id).unwrap().weight));
+            }
+        }
+    }
+
+    // without negative cycles, a shortest path has less than V arcs, so V - 1 rounds are enough.
+    let number_of_nodes = network.node_data.len();
+    for _ in 1..number_of_nodes {
+        if relax_all(network, &arcs).is_none() {
+            return Ok(());
+        }
+    }
+
+    // if an arc can still be relaxed, the node is affected by a negative cycle.
+    // walking back V times along predecessors surely ends up on the cycle.
+    match relax_all(network, &arcs) {
+        None => Ok(()),
+        Some(mut node_id) => {
+            for _ in 0..number_of_nodes {
+                node_id = network.data_of_node(node_id).unwrap().predecessor.unwrap();
+            }
+            Err(BellmanFordError::NegativeCycle(NegativeCycle { node: node_id }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn distances(network: &GraphNetwork<DijkstraNode<i64>, DijkstraArc<i64>>) -> Vec<i64> {
+        (0..network.node_data.len())
+            .map(|node_id| network.data_of_node(node_id).unwrap().distance)
+            .collect()
+    }
+
+    #[test]
+    fn test_bellman_ford() {
+        let mut network = network_factory::<i64>(vec![
+            (0, 1, 4),
+            (0, 2, 2),
+            (2, 1, -3),
+            (1, 3, 2),
+            (3, 4, -1),
+            (2, 4, 5),
+        ]);
+        network.add_node(network.data_of_node(0).unwrap().clone());
+        bellman_ford(&mut network, 0).unwrap();
+        assert_eq!(distances(&network), vec![0, -1, 2, 1, 0, i64::MAX]);
+        assert_eq!(network.data_of_node(1).unwrap().predecessor, Some(2));
+        assert_eq!(network.data_of_node(4).unwrap().predecessor, Some(3));
+        assert_eq!(network.data_of_node(5).unwrap().predecessor, None);
+    }
+
+    #[test]
+    fn test_bellman_ford_negative_cycle() {
+        // 1 -> 2 -> 3 -> 1 weighs -1 in total
+        let mut network = network_factory::<i64>(vec![
+            (0, 1, 1),
+            (1, 2, 2),
+            (2, 3, -4),
+            (3, 1, 1),
+            (3, 4, 1),
+        ]);
+        let Err(BellmanFordError::NegativeCycle(error)) = bellman_ford(&mut network, 0) else {
+            panic!("the negative cycle is not found");
+        };
+        assert!([1, 2, 3].contains(&error.node));
+
+        // the cycle is not reachable from 4
+        bellman_ford(&mut network, 4).unwrap();
+    }
+
+    #[test]
+    fn test_bellman_ford_invalid_start() {
+        let mut network = network_factory::<i64>(vec![(0, 1, 1), (1, 2, -1)]);
+        assert_eq!(bellman_ford(&mut network, 3), Err(BellmanFordError::InvalidStart(3)));
+        network.remove_node(2);
+        assert_eq!(bellman_ford(&mut network, 2), Err(BellmanFordError::InvalidStart(2)));
+    }
+}
@@ -1,37 +1,82 @@
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::fmt::{Display, Formatter, Result};
+use std::ops::ControlFlow;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::graph::*;
 use crate::mutable_heap::*;
+use crate::weight::*;
 
 #[derive(Debug, Clone)]
-pub struct DijkstraNode {
-    distance: Box<usize>,
-    heap_id: usize,
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DijkstraNode<W = usize> {
+    pub(crate) distance: W,
+    /// id in the heap while the node is in it. `None` if the node has not been pushed or has been settled
+    pub(crate) heap_id: Option<usize>,
+    /// the node right before this one on the shortest path found
+    pub(crate) predecessor: Option<NodeId>,
+    /// the arc from `predecessor` to this node
+    pub(crate) predecessor_arc: Option<ArcId>,
+}
+impl<W: Copy> DijkstraNode<W> {
+    /// distance from the start node of the last run. `Weight::INFINITY` if the node was not reached.
+    pub fn distance(&self) -> W {
+        self.distance
+    }
+    pub fn predecessor(&self) -> Option<NodeId> {
+        self.predecessor
+    }
+    pub fn predecessor_arc(&self) -> Option<ArcId> {
+        self.predecessor_arc
+    }
+}
+/// a node which has not been reached, as after `reset_distances`
+impl<W: Weight> Default for DijkstraNode<W> {
+    fn default() -> Self {
+        DijkstraNode {
+            distance: W::INFINITY,
+            heap_id: None,
+            predecessor: None,
+            predecessor_arc: None,
+        }
+    }
 }
-impl Display for DijkstraNode {
+impl<W: Display> Display for DijkstraNode<W> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(f, "{}", self.distance)
     }
 }
 
 #[derive(Debug)]
-pub struct DijkstraArc {
-    weight: usize,
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DijkstraArc<W = usize> {
+    pub(crate) weight: W,
 }
-impl Display for DijkstraArc {
+impl<W: Display> Display for DijkstraArc<W> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(f, "{}", self.weight)
     }
 }
 
-impl DijkstraArc {
-    pub fn new(weight: usize) -> Self {
+impl<W> DijkstraArc<W> {
+    pub fn new(weight: W) -> Self {
         DijkstraArc { weight }
     }
 }
 
-impl Clone for DijkstraArc {
+impl<W: Copy> DijkstraArc<W> {
+    pub fn weight(&self) -> W {
+        self.weight
+    }
+}
+
+impl<W: Copy> Clone for DijkstraArc<W> {
     fn clone(&self) -> Self {
         DijkstraArc {
             weight: self.weight,
@@ -39,80 +84,929 @@ impl Clone for DijkstraArc {
     }
 }
 
-pub fn dijkstra(network: &mut GraphNetwork<DijkstraNode, DijkstraArc>, start_node_id: NodeId) {
-    // fill distance with infinity
-    for node in &mut network.node_data {
-        node.as_mut().unwrap().distance = Box::new(usize::MAX);
+impl<W: Weight> GraphNetwork<DijkstraNode<W>, DijkstraArc<W>> {
+    /// distances of all the nodes indexed by `NodeId`. removed nodes are `Weight::INFINITY`.
+    pub fn distances(&self) -> Vec<W> {
+        self.node_data
+            .iter()
+            .map(|node| node.as_ref().map_or(W::INFINITY, |node| node.distance()))
+            .collect()
     }
-    // set start node distance to 0
-    network.mut_data_of_node(start_node_id).unwrap().distance = Box::new(0);
+    /// remove the node, keeping the distances between the remaining nodes (as in contraction hierarchies).
+    /// for each in-neighbor `u` and out-neighbor `v`, a shortcut arc `u -> v` weighted `w(u, id) + w(id, v)` is
+    /// added. if there is already an arc `u -> v`, its weight is lowered to that instead.
+    /// a shortcut is only needed if the path through the node is the only shortest one, so it is skipped
+    /// when a witness search (see `witness_search`) finds a path `u -> v` avoiding the node which is not longer.
+    /// self loops on the node and shortcuts `u -> u` are skipped as well, as they never shorten a path.
+    /// does nothing if the node does not exist.
+    pub fn contract_node(&mut self, id: NodeId) {
+        if !self.node_exists(id) {
+            return;
+        }
+        let weight_of = |network: &Self, arc_id: ArcId| network.data_of_arc(arc_id).unwrap().weight;
+        let incoming: Vec<(NodeId, W)> = self
+            .into_node(id)
+            .filter(|&(from, _)| from != id)
+            .map(|(from, arc_id)| (from, weight_of(self, arc_id)))
+            .collect();
+        let outgoing: Vec<(NodeId, W)> = self
+            .from_node(id)
+            .filter(|&(into, _)| into != id)
+            .map(|(into, arc_id)| (into, weight_of(self, arc_id)))
+            .collect();
 
-    // choices
-    let mut heap = FibonacciHeap::<usize>::new();
-    let mut heap_to_network = HashMap::<usize, usize>::new();
+        for &(from, weight_in) in incoming.iter() {
+            let shortcuts: Vec<(NodeId, W)> = outgoing
+                .iter()
+                .filter(|&&(into, _)| into != from)
+                // a path through the node which overflows is never relaxed, so it needs no shortcut
+                .filter_map(|&(into, weight_out)| Some((into, weight_in.checked_add(weight_out)?)))
+                .collect();
+            let Some(cap) = shortcuts.iter().map(|&(_, shortcut)| shortcut).max() else {
+                continue;
+            };
+            let witnesses = self.witness_search(from, id, cap);
 
-    for network_node_id in 0..network.node_data.len() {
-        if let Some(network_node) = network.mut_data_of_node(network_node_id) {
-            let heap_id = heap.push(*network_node.distance);
-            heap_to_network.insert(heap_id, network_node_id);
-            network_node.heap_id = heap_id;
+            for (into, shortcut) in shortcuts {
+                if witnesses.get(&into).is_some_and(|&witness| witness <= shortcut) {
+                    continue;
+                }
+                // with parallel arcs, the cheapest one decides the distance.
+                // it may be a shortcut added for a parallel arc from the node just before, so keep the smaller one.
+                let existing = self
+                    .between_nodes(from, into)
+                    .min_by_key(|&arc_id| weight_of(self, arc_id));
+                match existing {
+                    Some(arc_id) => {
+                        let arc = self.mut_data_of_arc(arc_id).unwrap();
+                        arc.weight = arc.weight.min(shortcut);
+                    }
+                    None => {
+                        self.connect(from, into, DijkstraArc::new(shortcut));
+                    }
+                }
+            }
+        }
+        self.remove_node(id);
+    }
+    /// distances from `from` avoiding the node `excluded`, bounded by `cap`: a local dijkstra which does not
+    /// explore beyond `cap`, so that it stays cheap on a large network. nodes farther than `cap` are absent.
+    fn witness_search(&self, from: NodeId, excluded: NodeId, cap: W) -> HashMap<NodeId, W> {
+        let mut distances = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((W::ZERO, from)));
+        while let Some(Reverse((distance, node_id))) = heap.pop() {
+            // lazy deletion in the same manner as `dijkstra_lazy`
+            if distances.contains_key(&node_id) {
+                continue;
+            }
+            distances.insert(node_id, distance);
+            for (into, arc_id) in self.from_node(node_id) {
+                if into == excluded || distances.contains_key(&into) {
+                    continue;
+                }
+                let weight = self.data_of_arc(arc_id).unwrap().weight;
+                let Some(new_distance) = distance.checked_add(weight) else {
+                    continue;
+                };
+                if new_distance <= cap {
+                    heap.push(Reverse((new_distance, into)));
+                }
+            }
         }
+        distances
     }
+    /// total weight of the path visiting `path` in order, taking the cheapest arc between each consecutive pair.
+    /// `None` if a pair is not connected (or a node does not exist). a path of zero or one node weighs `Weight::ZERO`.
+    pub fn path_weight(&self, path: &[NodeId]) -> Option<W> {
+        let mut total = W::ZERO;
+        for pair in path.windows(2) {
+            if !self.node_exists(pair[0]) || !self.node_exists(pair[1]) {
+                return None;
+            }
+            let weight = self
+                .between_nodes(pair[0], pair[1])
+                .map(|arc_id| self.data_of_arc(arc_id).unwrap().weight)
+                .min()?;
+            total = total + weight;
+        }
+        Some(total)
+    }
+    /// sum of the weights of all the arcs
+    pub fn total_arc_weight(&self) -> W {
+        self.iter_arcs().fold(W::ZERO, |total, (_, _, _, arc)| total + arc.weight)
+    }
+    /// same as `distances`, but `None` for unreachable (and removed) nodes
+    pub(crate) fn reached_distances(&self) -> Vec<Option<W>> {
+        self.distances()
+            .into_iter()
+            .map(Weight::finite)
+            .collect()
+    }
+}
 
-    loop {
-        // take closest node
-        let minimum = heap.pop();
-        if minimum.is_none() {
+/// check that every arc has a non-negative weight.
+/// dijkstra requires non-negative weights, and silently gives wrong distances otherwise (use `bellman_ford` instead).
+/// zero weights are accepted (e.g. the arcs reweighted by `johnson`). returns the first offending arc.
+pub fn validate_weights<W: Weight>(
+    network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+) -> std::result::Result<(), ArcId> {
+    for (arc_id, arc) in network.arc_data.iter().enumerate() {
+        if let Some(arc) = arc {
+            if arc.weight < W::ZERO {
+                return Err(arc_id);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// forget the result of the last run: distances are set to `Weight::INFINITY`, and predecessors and heap ids are cleared.
+/// every shortest path function calls this first, so that a network can be reused across queries.
+pub fn reset_distances<W: Weight>(network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>) {
+    // removed nodes are skipped
+    for (_, node) in network.node_data_mut() {
+        *node = DijkstraNode::default();
+    }
+}
+
+/// same as `reset_distances`, but only for the given nodes (removed or out-of-range ones are skipped).
+/// meant for localized re-computation: the caller is responsible for resetting the whole affected region,
+/// as the nodes left untouched keep the distances and predecessors of the last run, which may point into it.
+pub fn reset_distances_for<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    nodes: &[NodeId],
+) {
+    for &node_id in nodes {
+        if let Some(node) = network.mut_data_of_node(node_id) {
+            *node = DijkstraNode::default();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DijkstraError {
+    /// the start node is out of range or has been removed
+    InvalidStart(NodeId),
+}
+impl Display for DijkstraError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            DijkstraError::InvalidStart(node) => write!(f, "start node {} does not exist", node),
+        }
+    }
+}
+impl std::error::Error for DijkstraError {}
+
+pub(crate) fn check_start<W>(
+    network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+) -> std::result::Result<(), DijkstraError> {
+    match network.node_exists(start_node_id) {
+        true => Ok(()),
+        false => Err(DijkstraError::InvalidStart(start_node_id)),
+    }
+}
+
+/// single source shortest paths with a fibonacci heap.
+/// weights must be non-negative (see `validate_weights`).
+/// returns an error without touching the network if the start node does not exist.
+/// a start node without outgoing arcs is just settled at `Weight::ZERO`.
+pub fn dijkstra<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+) -> std::result::Result<(), DijkstraError> {
+    dijkstra_with_callback(network, start_node_id, |_, _| ControlFlow::Continue(()))
+}
+
+/// `dijkstra` for the callers which already know that the start node exists
+fn dijkstra_unchecked<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+) {
+    search(network, start_node_id, &mut DijkstraHeap::<W>::new(), |_, _| ControlFlow::Continue(()));
+}
+
+/// `dijkstra` which calls `on_settle` with each node and its final distance when the node is settled,
+/// in non-decreasing order of the distance.
+/// if `on_settle` returns `ControlFlow::Break`, the search stops there. the nodes settled so far have their final
+/// distances, and the others have tentative ones (or `Weight::INFINITY`).
+pub fn dijkstra_with_callback<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+    on_settle: impl FnMut(NodeId, W) -> ControlFlow<()>,
+) -> std::result::Result<(), DijkstraError> {
+    check_start(network, start_node_id)?;
+    // choices. the node id rides along with the distance, so that no map from heap ids to node ids is needed.
+    // ties are broken by the node id, which does not matter for the distances.
+    search(network, start_node_id, &mut DijkstraHeap::<W>::new(), on_settle);
+    Ok(())
+}
+
+/// `dijkstra` with any `MutableHeap` (e.g. `PairingHeap`) instead of the fibonacci heap.
+/// `heap` should be empty.
+pub fn dijkstra_with_heap<W: Weight, H: MutableHeap<(W, NodeId)>>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+    mut heap: H,
+) -> std::result::Result<(), DijkstraError> {
+    check_start(network, start_node_id)?;
+    search(network, start_node_id, &mut heap, |_, _| ControlFlow::Continue(()));
+    Ok(())
+}
+
+/// counts of the operations performed by a `dijkstra_instrumented` run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DijkstraStats {
+    pub pushes: usize,
+    pub pops: usize,
+    /// calls of `modify` on the heap, which always decrease the key in dijkstra
+    pub decrease_keys: usize,
+    /// arcs relaxed by `relax_neighbors`, i.e. the arcs from every settled node except the ones into nodes
+    /// at most as far as it (e.g. settled ones) and the ones whose distance overflows
+    pub relaxations: usize,
+}
+
+/// heap which counts the operations on the inner one
+struct CountingHeap<H> {
+    heap: H,
+    stats: DijkstraStats,
+}
+impl<K, H: MutableHeap<K>> MutableHeap<K> for CountingHeap<H> {
+    fn push(&mut self, key: K) -> usize {
+        self.stats.pushes += 1;
+        self.heap.push(key)
+    }
+    fn pop(&mut self) -> Option<(usize, K)> {
+        let popped = self.heap.pop();
+        self.stats.pops += popped.is_some() as usize;
+        popped
+    }
+    fn get_min(&self) -> Option<usize> {
+        self.heap.get_min()
+    }
+    fn modify(&mut self, id: usize, new_key: K) {
+        self.stats.decrease_keys += 1;
+        self.heap.modify(id, new_key);
+    }
+}
+
+/// `dijkstra` which counts the heap operations and relaxations, e.g. to compare heaps on a network.
+/// the plain `dijkstra` does not pay for the counting.
+pub fn dijkstra_instrumented<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+) -> std::result::Result<DijkstraStats, DijkstraError> {
+    check_start(network, start_node_id)?;
+    let mut heap = CountingHeap {
+        heap: DijkstraHeap::<W>::new(),
+        stats: DijkstraStats::default(),
+    };
+    let relaxations = search(network, start_node_id, &mut heap, |_, _| ControlFlow::Continue(()));
+    heap.stats.relaxations = relaxations;
+    Ok(heap.stats)
+}
+
+// the node id in the key breaks ties between equal distances, so the pop order never depends on the heap
+type DijkstraHeap<W> = FibonacciHeap<(W, NodeId)>;
+
+/// returns the number of arcs relaxed (see `settle`)
+fn search<W: Weight, H: MutableHeap<(W, NodeId)>>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+    heap: &mut H,
+    on_settle: impl FnMut(NodeId, W) -> ControlFlow<()>,
+) -> usize {
+    reset_distances(network);
+
+    // only the start node is pushed here. the others are pushed when they are reached for the first time,
+    // so that unreachable nodes never enter the heap.
+    let start_node = network.mut_data_of_node(start_node_id).unwrap();
+    start_node.distance = W::ZERO;
+    start_node.heap_id = Some(heap.push((W::ZERO, start_node_id)));
+
+    settle(network, heap, on_settle)
+}
+
+/// the main loop of dijkstra. settle the nodes in the heap one by one, relaxing the arcs from them.
+/// nodes in the heap must have their distance and `heap_id` set.
+/// returns the number of arcs relaxed, summed over `relax_neighbors`.
+fn settle<W: Weight, H: MutableHeap<(W, NodeId)>>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    heap: &mut H,
+    mut on_settle: impl FnMut(NodeId, W) -> ControlFlow<()>,
+) -> usize {
+    let mut relaxations = 0;
+    // take closest node
+    while let Some((_, (current_network_node_distance, current_network_node_id))) = heap.pop() {
+        network.mut_data_of_node(current_network_node_id).unwrap().heap_id = None;
+        if on_settle(current_network_node_id, current_network_node_distance).is_break() {
             break;
         }
-        let (minimum_heap_id, _) = minimum.unwrap();
-        let current_network_node_id = *heap_to_network.get(&minimum_heap_id).unwrap();
-        let current_network_node_distance = *network
-            .mut_data_of_node(current_network_node_id)
-            .unwrap()
-            .distance;
 
-        let children: Vec<(NodeId, ArcId)> = network.from_node(current_network_node_id).collect();
+        relaxations += relax_neighbors(
+            network,
+            current_network_node_id,
+            current_network_node_distance,
+            |node_id, node| push_or_decrease(heap, node, node_id),
+        );
+    }
+    relaxations
+}
+
+/// relax every arc from the node settled at `current_distance`.
+/// each node whose distance is improved gets the new distance and predecessor, and is passed to `on_improve`
+/// (e.g. to update the heap). returns the number of arcs relaxed: an arc into a node at most as far as the current
+/// one (e.g. a settled one) cannot improve it and is skipped, as well as an arc whose distance overflows.
+fn relax_neighbors<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    current_node_id: NodeId,
+    current_distance: W,
+    mut on_improve: impl FnMut(NodeId, &mut DijkstraNode<W>),
+) -> usize {
+    let children: Vec<(NodeId, ArcId, W)> = network
+        .arcs_of_node(current_node_id)
+        .map(|(into, arc_id, arc)| (into, arc_id, arc.weight))
+        .collect();
+
+    let mut relaxations = 0;
+    for (node_id, arc_id, weight) in children.into_iter() {
+        let node = network.mut_data_of_node(node_id).unwrap();
+        if node.distance <= current_distance {
+            // `current_distance + weight` is never smaller, as weights are non-negative
+            continue;
+        }
+        let Some(new_distance) = current_distance.checked_add(weight) else {
+            // the distance is not representable, so the arc cannot improve anything
+            continue;
+        };
+        relaxations += 1;
+        if new_distance < node.distance {
+            node.distance = new_distance;
+            node.predecessor = Some(current_node_id);
+            node.predecessor_arc = Some(arc_id);
+            on_improve(node_id, node);
+        }
+    }
+    relaxations
+}
+
+/// decrease the key if the node is already in the heap, insert it otherwise
+fn push_or_decrease<W: Weight, H: MutableHeap<(W, NodeId)>>(
+    heap: &mut H,
+    node: &mut DijkstraNode<W>,
+    node_id: NodeId,
+) {
+    match node.heap_id {
+        Some(heap_id) => heap.modify(heap_id, (node.distance, node_id)),
+        None => node.heap_id = Some(heap.push((node.distance, node_id))),
+    }
+}
+
+/// set the weight of the arc and returns the former one, `None` if the arc does not exist.
+/// the distances are not updated. call `dijkstra_update` afterwards to repair them.
+pub fn update_arc_weight<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    arc: ArcId,
+    new_weight: W,
+) -> Option<W> {
+    let arc = network.mut_data_of_arc(arc)?;
+    Some(std::mem::replace(&mut arc.weight, new_weight))
+}
+
+/// repair the result of the last complete `dijkstra` run after the weight of `arc` has been changed
+/// (e.g. with `update_arc_weight`). the result is the same as running `dijkstra` again from the same start node.
+/// - on a decrease, the improvement is propagated from the head of the arc.
+/// - on an increase of an arc of the shortest path tree, only the subtree below it is recomputed.
+/// - an increase of an arc out of the tree changes nothing.
+pub fn dijkstra_update<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    arc: ArcId,
+) {
+    let Some((from, into)) = network.endpoints_of_arc(arc) else {
+        return;
+    };
+    let from_distance = network.data_of_node(from).unwrap().distance;
+    let into_node = network.data_of_node(into).unwrap();
+    let is_tree_arc = into_node.predecessor_arc == Some(arc);
+    let into_distance = into_node.distance;
+    let mut heap = DijkstraHeap::<W>::new();
+
+    // distance through the arc. `None` if `from` is not reached or the sum overflows as in `relax_neighbors`
+    let weight = network.data_of_arc(arc).unwrap().weight;
+    let through_arc = from_distance.finite().and_then(|distance| distance.checked_add(weight));
 
-        for (node_id, arc_id) in children.into_iter() {
-            let arc = network.data_of_arc(arc_id).unwrap();
-            let new_distance = current_network_node_distance + arc.weight;
+    if through_arc.is_some_and(|distance| distance < into_distance) {
+        // decreased: improve the head and propagate
+        let node = network.mut_data_of_node(into).unwrap();
+        node.distance = through_arc.unwrap();
+        node.predecessor = Some(from);
+        node.predecessor_arc = Some(arc);
+        push_or_decrease(&mut heap, node, into);
+    } else if is_tree_arc && through_arc != Some(into_distance) {
+        // increased on the tree: every node below `into` may have lost its shortest path
+        let mut subtree = vec![into];
+        let mut index = 0;
+        while index < subtree.len() {
+            let parent = subtree[index];
+            index += 1;
+            for (child, child_arc) in network.from_node(parent) {
+                if network.data_of_node(child).unwrap().predecessor_arc == Some(child_arc) {
+                    subtree.push(child);
+                }
+            }
+        }
+        for &node_id in subtree.iter() {
             let node = network.mut_data_of_node(node_id).unwrap();
-            if new_distance < *node.distance {
-                *node.distance = new_distance;
-                heap.modify(node.heap_id, new_distance);
+            node.distance = W::INFINITY;
+            node.predecessor = None;
+            node.predecessor_arc = None;
+        }
+        // the nodes out of the subtree keep their distances. enter the subtree from them
+        for &node_id in subtree.iter() {
+            let mut best: Option<(W, NodeId, ArcId)> = None;
+            for (predecessor, arc_id) in network.into_node(node_id) {
+                let node = network.data_of_node(predecessor).unwrap();
+                let Some(predecessor_distance) = node.distance.finite() else {
+                    continue;
+                };
+                let weight = network.data_of_arc(arc_id).unwrap().weight;
+                let Some(distance) = predecessor_distance.checked_add(weight) else {
+                    continue;
+                };
+                if best.is_none_or(|(best_distance, _, _)| distance < best_distance) {
+                    best = Some((distance, predecessor, arc_id));
+                }
+            }
+            if let Some((distance, predecessor, arc_id)) = best {
+                let node = network.mut_data_of_node(node_id).unwrap();
+                node.distance = distance;
+                node.predecessor = Some(predecessor);
+                node.predecessor_arc = Some(arc_id);
+                push_or_decrease(&mut heap, node, node_id);
             }
         }
     }
+
+    settle(network, &mut heap, |_, _| ControlFlow::Continue(()));
 }
 
-pub fn simple_dijkstra(
+/// `dijkstra` which also returns the nodes in the order they are settled. the start node comes first,
+/// the distances are non-decreasing along the order, and unreachable nodes do not appear.
+/// the order is deterministic whatever the heap is: the heap key is `(distance, node id)`,
+/// so nodes at the same distance are settled in ascending order of their ids.
+/// likewise, among equally short paths, a node keeps the predecessor which is settled first.
+pub fn dijkstra_settle_order<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+) -> std::result::Result<Vec<NodeId>, DijkstraError> {
+    let mut order = Vec::new();
+    dijkstra_with_callback(network, start_node_id, |node_id, _| {
+        order.push(node_id);
+        ControlFlow::Continue(())
+    })?;
+    Ok(order)
+}
+
+/// the closest node among `targets` and its distance, `None` if no target is reachable.
+/// the search stops as soon as the first target is settled. the start node itself counts if it is in `targets`.
+pub fn dijkstra_to_any<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+    targets: &HashSet<NodeId>,
+) -> std::result::Result<Option<(NodeId, W)>, DijkstraError> {
+    let mut found = None;
+    dijkstra_with_callback(network, start_node_id, |node_id, distance| {
+        if targets.contains(&node_id) {
+            found = Some((node_id, distance));
+            return ControlFlow::Break(());
+        }
+        ControlFlow::Continue(())
+    })?;
+    Ok(found)
+}
+
+/// `dijkstra` from several seeds at once, each starting at its given distance instead of `Weight::ZERO`,
+/// as if from a virtual source with an arc of that weight to each seed. all seeds at zero is multi-source dijkstra.
+/// a seed listed more than once starts at the smallest of its distances.
+/// returns an error without touching the network if any seed does not exist.
+pub fn dijkstra_seeded<W: Weight>(
+    network: &mut GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    initial: &[(NodeId, W)],
+) -> std::result::Result<(), DijkstraError> {
+    for &(node_id, _) in initial {
+        check_start(network, node_id)?;
+    }
+    reset_distances(network);
+
+    let mut heap = DijkstraHeap::<W>::new();
+    for &(node_id, distance) in initial {
+        let node = network.mut_data_of_node(node_id).unwrap();
+        if distance < node.distance {
+            node.distance = distance;
+            push_or_decrease(&mut heap, node, node_id);
+        }
+    }
+    settle(network, &mut heap, |_, _| ControlFlow::Continue(()));
+    Ok(())
+}
+
+/// distances from the start node indexed by `NodeId`, `None` for unreachable (and removed) nodes.
+/// unlike `dijkstra`, the given network is left untouched: the search runs on a copy of it.
+pub fn shortest_distances<W: Weight>(
+    network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+) -> std::result::Result<Vec<Option<W>>, DijkstraError> {
+    check_start(network, start_node_id)?;
+    let mut network = network.clone();
+    dijkstra_unchecked(&mut network, start_node_id);
+    Ok(network.reached_distances())
+}
+
+/// for each node, the arc by which it is reached on the shortest path from the start node.
+/// `None` for the start node itself and for unreachable or removed nodes.
+/// the arcs form a tree rooted at the start node. like `shortest_distances`, the search runs on a copy of the network.
+pub fn shortest_path_tree<W: Weight>(
+    network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+) -> std::result::Result<Vec<Option<ArcId>>, DijkstraError> {
+    check_start(network, start_node_id)?;
+    let mut network = network.clone();
+    dijkstra_unchecked(&mut network, start_node_id);
+    Ok(network
+        .node_data
+        .iter()
+        .map(|node| node.as_ref().and_then(|node| node.predecessor_arc))
+        .collect())
+}
+
+/// for each node, every predecessor on some shortest path from the start node, i.e. every `u` with an arc
+/// `(u -> v)` with `distance[u] + weight == distance[v]`, in the order of `into_node` and without duplicates.
+/// together they form the DAG of all the shortest paths (a cycle may appear only through zero-weight arcs).
+/// empty for the start node and for unreachable or removed nodes. the search runs on a copy of the network.
+pub fn shortest_path_dag<W: Weight>(
+    network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+) -> std::result::Result<Vec<Vec<NodeId>>, DijkstraError> {
+    let distances = shortest_distances(network, start_node_id)?;
+    Ok(dag_from_distances(network, start_node_id, &distances))
+}
+
+/// `shortest_path_dag` from the distances of a finished run
+fn dag_from_distances<W: Weight>(
+    network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+    distances: &[Option<W>],
+) -> Vec<Vec<NodeId>> {
+    (0..network.node_data.len())
+        .map(|into| {
+            let mut predecessors = Vec::new();
+            let Some(into_distance) = distances[into] else {
+                return predecessors;
+            };
+            if into == start_node_id {
+                return predecessors;
+            }
+            for (from, arc_id) in network.into_node(into) {
+                let Some(from_distance) = distances[from] else {
+                    continue;
+                };
+                let weight = network.data_of_arc(arc_id).unwrap().weight;
+                let on_shortest_path = from_distance.checked_add(weight) == Some(into_distance);
+                if on_shortest_path && !predecessors.contains(&from) {
+                    predecessors.push(from);
+                }
+            }
+            predecessors
+        })
+        .collect()
+}
+
+/// betweenness centrality of every node by Brandes' algorithm: the sum over all the pairs `(s, t)` of other nodes
+/// of the fraction of the shortest paths from `s` to `t` passing through the node. arcs are directed and
+/// the scores are not normalized. removed nodes score 0. O(V · (E + V log V)).
+/// weights must be non-negative (see `validate_weights`). the path counts are summed in the settle order, where
+/// nodes at the same distance come in ascending order of their ids (see `dijkstra_settle_order`). so a zero-weight
+/// arc `u -> v` with `v < u` is not supported: the count of `u` would be read before it is complete.
+/// this is checked by a `debug_assert`.
+pub fn betweenness_centrality<W: Weight>(
+    network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+) -> Vec<f64> {
+    let number_of_nodes = network.node_data.len();
+    let mut centrality = vec![0.0; number_of_nodes];
+    let mut network = network.clone();
+    for source in 0..number_of_nodes {
+        if !network.is_node_in(source) {
+            continue;
+        }
+        let mut order = Vec::new();
+        search(&mut network, source, &mut DijkstraHeap::<W>::new(), |node_id, _| {
+            order.push(node_id);
+            ControlFlow::Continue(())
+        });
+        let predecessors = dag_from_distances(&network, source, &network.reached_distances());
+
+        // number of the shortest paths from the source, in the settle order
+        let mut path_counts = vec![0.0; number_of_nodes];
+        path_counts[source] = 1.0;
+        for &node_id in order.iter().skip(1) {
+            // every reached node has a path, so a count of 0 is one which is not summed yet
+            debug_assert!(
+                predecessors[node_id].iter().all(|&p| path_counts[p] > 0.0),
+                "node {} is reached by a zero-weight arc from a node settled after it",
+                node_id
+            );
+            path_counts[node_id] = predecessors[node_id].iter().map(|&p| path_counts[p]).sum();
+        }
+        // dependencies of the source on each node, accumulated from the farthest nodes
+        let mut dependencies = vec![0.0; number_of_nodes];
+        for &node_id in order.iter().rev() {
+            for &predecessor in predecessors[node_id].iter() {
+                dependencies[predecessor] +=
+                    path_counts[predecessor] / path_counts[node_id] * (1.0 + dependencies[node_id]);
+            }
+            if node_id != source {
+                centrality[node_id] += dependencies[node_id];
+            }
+        }
+    }
+    centrality
+}
+
+/// result of a `dijkstra_solve` run, which can be queried without the network. both are indexed by `NodeId`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DijkstraSolution<W = usize> {
+    pub start: NodeId,
+    /// `None` for unreachable and removed nodes
+    pub distances: Vec<Option<W>>,
+    /// the node right before each node on its shortest path. `None` for the start node and unreached nodes
+    pub predecessors: Vec<Option<NodeId>>,
+}
+impl<W> DijkstraSolution<W> {
+    /// nodes on the shortest path from the start node to the target, both inclusive.
+    /// `None` if the target is not reached.
+    pub fn path_to(&self, target: NodeId) -> Option<Vec<NodeId>> {
+        self.distances.get(target)?.as_ref()?;
+        let mut path = vec![target];
+        while let Some(predecessor) = self.predecessors[*path.last().unwrap()] {
+            path.push(predecessor);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// distances and predecessors from the start node, as a self-contained `DijkstraSolution`.
+/// like `shortest_distances`, the search runs on a copy of the network.
+pub fn dijkstra_solve<W: Weight>(
+    network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    start_node_id: NodeId,
+) -> std::result::Result<DijkstraSolution<W>, DijkstraError> {
+    check_start(network, start_node_id)?;
+    let mut network = network.clone();
+    dijkstra_unchecked(&mut network, start_node_id);
+    Ok(DijkstraSolution {
+        start: start_node_id,
+        distances: network.reached_distances(),
+        predecessors: network
+            .node_data
+            .iter()
+            .map(|node| node.as_ref().and_then(|node| node.predecessor))
+            .collect(),
+    })
+}
+
+/// distance matrix. `matrix[from][into]` is the distance from `from` to `into`, `None` if unreachable.
+/// rows of removed nodes are all `None`.
+/// runs `dijkstra` from every node, so it takes O(V · (E + V log V)).
+pub fn all_pairs_shortest_paths<W: Weight>(
+    network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+) -> Vec<Vec<Option<W>>> {
+    let number_of_nodes = network.node_data.len();
+    // `dijkstra` resets the distances by itself, so one copy is enough for all the runs
+    let mut network = network.clone();
+    (0..number_of_nodes)
+        .map(|start_node_id| {
+            if network.data_of_node(start_node_id).is_none() {
+                return vec![None; number_of_nodes];
+            }
+            dijkstra_unchecked(&mut network, start_node_id);
+            network.reached_distances()
+        })
+        .collect()
+}
+
+/// same distance matrix as `all_pairs_shortest_paths`, by the classic triple loop. O(V³) time and O(V²) memory.
+/// negative weights are fine as long as there is no negative cycle.
+/// simpler than running `dijkstra` V times, and competitive on small dense networks.
+pub fn floyd_warshall<W: Weight>(
+    network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+) -> Vec<Vec<Option<W>>> {
+    let number_of_nodes = network.node_data.len();
+    // row-major, `W::INFINITY` for no path
+    let mut matrix = vec![W::INFINITY; number_of_nodes * number_of_nodes];
+    for (node_id, _) in network.iter_nodes() {
+        matrix[node_id * number_of_nodes + node_id] = W::ZERO;
+    }
+    for (_, from, into, arc) in network.iter_arcs() {
+        let cell = &mut matrix[from * number_of_nodes + into];
+        *cell = (*cell).min(arc.weight);
+    }
+
+    for via in 0..number_of_nodes {
+        let via_row = matrix[via * number_of_nodes..(via + 1) * number_of_nodes].to_vec();
+        for row in matrix.chunks_mut(number_of_nodes) {
+            let from_via = row[via];
+            if from_via == W::INFINITY {
+                continue;
+            }
+            for (cell, &via_into) in row.iter_mut().zip(via_row.iter()) {
+                // `None` if `via_into` is `W::INFINITY` or the sum overflows, as in `relax_neighbors`
+                if let Some(through) = from_via.checked_add(via_into) {
+                    if through < *cell {
+                        *cell = through;
+                    }
+                }
+            }
+        }
+    }
+
+    matrix
+        .chunks(number_of_nodes.max(1))
+        .map(|row| row.iter().copied().map(Weight::finite).collect())
+        .collect()
+}
+
+/// the largest distance from the node to the nodes reachable from it (`Weight::ZERO` if it reaches none).
+/// `None` if the node does not exist. the search runs on a copy of the network like `shortest_distances`.
+pub fn eccentricity<W: Weight>(
+    network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    node_id: NodeId,
+) -> Option<W> {
+    shortest_distances(network, node_id).ok()?.into_iter().flatten().max()
+}
+
+/// the largest eccentricity over all the nodes, i.e. the longest shortest path.
+/// only reachable pairs count, so the diameter of a disconnected network is not infinite
+/// (check `is_strongly_connected` for that). `None` if the network has no nodes.
+pub fn diameter<W: Weight>(network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>) -> Option<W> {
+    // one copy for all the runs in the same manner as `all_pairs_shortest_paths`
+    let mut network = network.clone();
+    let node_ids: Vec<NodeId> = network.iter_nodes().map(|(node_id, _)| node_id).collect();
+    node_ids
+        .into_iter()
+        .filter_map(|start_node_id| {
+            dijkstra_unchecked(&mut network, start_node_id);
+            network.reached_distances().into_iter().flatten().max()
+        })
+        .max()
+}
+
+/// distances from each of `sources`, computed in parallel. `result[i]` is the same as `shortest_distances(network, sources[i])`.
+/// each worker thread runs on its own copy of the network, so the given network is not touched.
+/// returns an error if any of the sources does not exist.
+#[cfg(feature = "rayon")]
+pub fn parallel_all_sources<W: Weight + Send + Sync>(
+    network: &GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+    sources: &[NodeId],
+) -> std::result::Result<Vec<Vec<Option<W>>>, DijkstraError> {
+    sources
+        .par_iter()
+        .map_init(
+            || network.clone(),
+            |network, &start_node_id| {
+                dijkstra(network, start_node_id)?;
+                Ok(network.reached_distances())
+            },
+        )
+        .collect()
+}
+
+/// single source algorithms `shortest_paths_auto` chooses from.
+/// there is no adjacency matrix variant: dense networks go to `simple_dijkstra` as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SingleSourceAlgorithm {
+    /// `simple_dijkstra`, an O(V²) scan as on an adjacency matrix
+    Simple,
+    /// `dijkstra_lazy`, a binary heap
+    Lazy,
+}
+
+/// when `shortest_paths_auto_with` leaves the binary heap for `simple_dijkstra` (see `benches/test.rs`).
+/// the scan is far slower on large sparse networks, but close to the heap on small or dense ones,
+/// where it saves the lazy heap which can grow up to one entry per arc.
+/// by default it is chosen up to 16 nodes or from `DENSE_THRESHOLD`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoThresholds {
+    /// networks with at most this many nodes are small
+    pub small_nodes: usize,
+    /// networks of at least this `GraphNetwork::density` are dense
+    pub dense: f64,
+}
+impl Default for AutoThresholds {
+    fn default() -> Self {
+        AutoThresholds {
+            small_nodes: 16,
+            dense: DENSE_THRESHOLD,
+        }
+    }
+}
+
+/// `SingleSourceAlgorithm::Simple` for small or dense networks, `SingleSourceAlgorithm::Lazy` otherwise
+pub fn choose_algorithm(
+    network: &GraphNetwork<DijkstraNode, DijkstraArc>,
+    thresholds: &AutoThresholds,
+) -> SingleSourceAlgorithm {
+    if network.node_count() <= thresholds.small_nodes || network.density() >= thresholds.dense {
+        SingleSourceAlgorithm::Simple
+    } else {
+        SingleSourceAlgorithm::Lazy
+    }
+}
+
+/// `shortest_distances` by the algorithm which suits the shape of the network, with the default thresholds
+pub fn shortest_paths_auto(
+    network: &GraphNetwork<DijkstraNode, DijkstraArc>,
+    start_node_id: NodeId,
+) -> std::result::Result<Vec<Option<usize>>, DijkstraError> {
+    shortest_paths_auto_with(network, start_node_id, &AutoThresholds::default())
+}
+
+/// `shortest_paths_auto` with the given thresholds (see `choose_algorithm`).
+/// the search runs on a copy of the network like `shortest_distances`.
+pub fn shortest_paths_auto_with(
+    network: &GraphNetwork<DijkstraNode, DijkstraArc>,
+    start_node_id: NodeId,
+    thresholds: &AutoThresholds,
+) -> std::result::Result<Vec<Option<usize>>, DijkstraError> {
+    let algorithm = choose_algorithm(network, thresholds);
+    let mut network = network.clone();
+    match algorithm {
+        SingleSourceAlgorithm::Simple => simple_dijkstra(&mut network, start_node_id)?,
+        SingleSourceAlgorithm::Lazy => dijkstra_lazy(&mut network, start_node_id)?,
+    }
+    Ok(network.reached_distances())
+}
+
+/// single source shortest paths with a plain binary heap.
+/// instead of decreasing keys, a new entry is pushed on every relaxation and stale entries are skipped when popped.
+/// weights must be non-negative (see `validate_weights`).
+pub fn dijkstra_lazy(
     network: &mut GraphNetwork<DijkstraNode, DijkstraArc>,
     start_node_id: NodeId,
-) {
-    // fill distance with infinity
-    for node in &mut network.node_data {
-        node.as_mut().unwrap().distance = Box::new(usize::MAX);
+) -> std::result::Result<(), DijkstraError> {
+    check_start(network, start_node_id)?;
+    reset_distances(network);
+    // set start node distance to 0
+    network.mut_data_of_node(start_node_id).unwrap().distance = 0;
+
+    // choices. `Reverse` turns the max-heap into a min-heap
+    let mut heap = BinaryHeap::<Reverse<(usize, NodeId)>>::new();
+    heap.push(Reverse((0, start_node_id)));
+
+    while let Some(Reverse((current_node_distance, current_node_id))) = heap.pop() {
+        if current_node_distance > network.data_of_node(current_node_id).unwrap().distance {
+            // stale entry. the node is already settled with a shorter distance
+            continue;
+        }
+
+        relax_neighbors(network, current_node_id, current_node_distance, |node_id, node| {
+            heap.push(Reverse((node.distance, node_id)));
+        });
     }
+    Ok(())
+}
+
+/// single source shortest paths, scanning all the nodes to find the closest one. O(V^2).
+/// weights must be non-negative (see `validate_weights`).
+pub fn simple_dijkstra(
+    network: &mut GraphNetwork<DijkstraNode, DijkstraArc>,
+    start_node_id: NodeId,
+) -> std::result::Result<(), DijkstraError> {
+    check_start(network, start_node_id)?;
+    reset_distances(network);
     // set start node distance to 0
-    network.mut_data_of_node(start_node_id).unwrap().distance = Box::new(0);
+    network.mut_data_of_node(start_node_id).unwrap().distance = 0;
 
     // choices
     let mut unprocessed_nodes: Vec<usize> = (0..network.node_data.len()).collect();
 
     loop {
-        // take closest node
+        // take closest node. the comparison is strict from `usize::MAX`, so an unreached node is never taken:
+        // once only unreached nodes are left, none is taken and the loop ends.
+        // thus nothing is ever added to `usize::MAX`.
         let mut current_node_id = None;
         let mut minimum_distance = usize::MAX;
         let mut new_unprocessed_nodes = Vec::new();
         for node_id in unprocessed_nodes.into_iter() {
             if let Some(node) = network.data_of_node(node_id) {
-                if *node.distance < minimum_distance {
+                if node.distance < minimum_distance {
                     if let Some(current_node_id) = current_node_id {
                         new_unprocessed_nodes.push(current_node_id);
                     };
                     current_node_id = Some(node_id);
-                    minimum_distance = *node.distance;
+                    minimum_distance = node.distance;
                 } else {
                     new_unprocessed_nodes.push(node_id);
                 }
@@ -125,39 +1019,102 @@ pub fn simple_dijkstra(
         }
 
         let current_node_id = current_node_id.unwrap();
-        let current_node_distance = *network.mut_data_of_node(current_node_id).unwrap().distance;
+        let current_node_distance = network.mut_data_of_node(current_node_id).unwrap().distance;
+        debug_assert!(current_node_distance != usize::MAX);
 
-        let children: Vec<(NodeId, ArcId)> = network.from_node(current_node_id).collect();
+        relax_neighbors(network, current_node_id, current_node_distance, |_, _| {});
+    }
+    Ok(())
+}
 
-        for (node_id, arc_id) in children.into_iter() {
-            let arc = network.data_of_arc(arc_id).unwrap();
-            let new_distance = current_node_distance + arc.weight;
-            let node = network.mut_data_of_node(node_id).unwrap();
-            if new_distance < *node.distance {
-                *node.distance = new_distance;
-            }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DagError {
+    /// the start node is out of range or has been removed
+    InvalidStart(NodeId),
+    /// the network has a cycle
+    Cycle(CycleError),
+}
+impl Display for DagError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            DagError::InvalidStart(node) => write!(f, "start node {} does not exist", node),
+            DagError::Cycle(error) => write!(f, "{}", error),
         }
     }
 }
+impl std::error::Error for DagError {}
+impl From<DijkstraError> for DagError {
+    fn from(error: DijkstraError) -> Self {
+        match error {
+            DijkstraError::InvalidStart(node) => DagError::InvalidStart(node),
+        }
+    }
+}
+impl From<CycleError> for DagError {
+    fn from(error: CycleError) -> Self {
+        DagError::Cycle(error)
+    }
+}
 
-pub fn network_factory(
-    arcs: Vec<(NodeId, NodeId, usize)>,
-) -> GraphNetwork<DijkstraNode, DijkstraArc> {
-    let mut network = GraphNetwork::<DijkstraNode, DijkstraArc>::new();
-    let mut max_node_id: usize = 0;
-    for (from, to, _) in arcs.iter() {
-        max_node_id = max_node_id.max(*from).max(*to);
+/// shortest paths on a directed acyclic graph.
+/// relaxing arcs in topological order settles every node at once, so no heap is needed and it runs in O(V + E).
+/// returns an error without touching distances if the start node does not exist or the network has a cycle.
+pub fn dag_shortest_paths(
+    network: &mut GraphNetwork<DijkstraNode, DijkstraArc>,
+    start_node_id: NodeId,
+) -> std::result::Result<(), DagError> {
+    check_start(network, start_node_id)?;
+    let order = network.topological_sort()?;
+
+    reset_distances(network);
+    // set start node distance to 0
+    network.mut_data_of_node(start_node_id).unwrap().distance = 0;
+
+    for current_node_id in order.into_iter() {
+        let current_node_distance = network.data_of_node(current_node_id).unwrap().distance;
+        if current_node_distance == usize::MAX {
+            // not reachable from the start node
+            continue;
+        }
+
+        relax_neighbors(network, current_node_id, current_node_distance, |_, _| {});
     }
+    Ok(())
+}
+
+/// network with `number_of_nodes` nodes and no arcs, with room for `number_of_arcs` arcs
+pub(crate) fn empty_network<W: Weight>(
+    number_of_nodes: usize,
+    number_of_arcs: usize,
+) -> GraphNetwork<DijkstraNode<W>, DijkstraArc<W>> {
+    let mut network = GraphNetwork::with_capacity(number_of_nodes, number_of_arcs);
     network.add_nodes(
         vec![
             DijkstraNode {
-                distance: Box::new(usize::MAX),
-                heap_id: usize::default()
+                distance: W::INFINITY,
+                heap_id: None,
+                predecessor: None,
+                predecessor_arc: None,
             };
-            max_node_id + 1
+            number_of_nodes
         ]
         .into_iter(),
     );
+    network
+}
+
+/// network with the given arcs. nodes `0..=(the largest id in arcs)` are created,
+/// so an empty list gives a network with no nodes (use `GraphNetworkBuilder` for isolated nodes).
+/// every arc is kept, including parallel ones (see `network_factory_dedup`).
+pub fn network_factory<W: Weight>(
+    arcs: Vec<(NodeId, NodeId, W)>,
+) -> GraphNetwork<DijkstraNode<W>, DijkstraArc<W>> {
+    let number_of_nodes = arcs
+        .iter()
+        .map(|(from, to, _)| from.max(to) + 1)
+        .max()
+        .unwrap_or(0);
+    let mut network = empty_network(number_of_nodes, arcs.len());
     network.bulk_connect(
         arcs.into_iter()
             .map(|(from, to, weight)| (from, to, DijkstraArc::new(weight))),
@@ -165,34 +1122,1195 @@ pub fn network_factory(
     network
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+/// network with `number_of_nodes` nodes and the arcs from the iterator, consumed lazily.
+/// unlike `network_factory`, the edge list is never held as a whole, e.g. when read from a large file line by line.
+/// panics if an arc refers to a node not less than `number_of_nodes`.
+pub fn network_from_edges<W: Weight, I: Iterator<Item = (NodeId, NodeId, W)>>(
+    edges: I,
+    number_of_nodes: usize,
+) -> GraphNetwork<DijkstraNode<W>, DijkstraArc<W>> {
+    let mut network = empty_network(number_of_nodes, edges.size_hint().0);
+    network.bulk_connect(edges.map(|(from, to, weight)| (from, to, DijkstraArc::new(weight))));
+    network
+}
 
-    fn mini_instance() -> GraphNetwork<DijkstraNode, DijkstraArc> {
-        network_factory(vec![
-            (0, 1, 1),
-            (0, 2, 3),
-            (0, 3, 2),
-            (1, 2, 1),
-            (3, 4, 2),
-            (4, 3, 2),
-            (4, 5, 2),
-            (5, 3, 2),
-        ])
+/// same as `network_factory`, but parallel arcs (same `from` and `into`) are collapsed into one with the minimum weight.
+/// distances are the same as with `network_factory`, as only the lightest of parallel arcs can be on a shortest path.
+/// the remaining arcs keep the order of their first appearance.
+pub fn network_factory_dedup<W: Weight>(
+    arcs: Vec<(NodeId, NodeId, W)>,
+) -> GraphNetwork<DijkstraNode<W>, DijkstraArc<W>> {
+    let mut position_of_pair = HashMap::<(NodeId, NodeId), usize>::new();
+    let mut deduped: Vec<(NodeId, NodeId, W)> = Vec::new();
+    for (from, into, weight) in arcs {
+        match position_of_pair.entry((from, into)) {
+            Entry::Occupied(position) => {
+                let kept = &mut deduped[*position.get()].2;
+                *kept = (*kept).min(weight);
+            }
+            Entry::Vacant(position) => {
+                position.insert(deduped.len());
+                deduped.push((from, into, weight));
+            }
+        }
     }
+    network_factory(deduped)
+}
 
-    #[test]
-    fn test_dijkstra() {
-        let mut network = mini_instance();
-        dijkstra(&mut network, 0);
-        println!("Network: \n{}", network);
+/// an arc refers to a node that has not been added to the builder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndeclaredNode {
+    pub node: NodeId,
+}
+impl Display for UndeclaredNode {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "node {} is not declared", self.node)
     }
+}
+impl std::error::Error for UndeclaredNode {}
 
-    #[test]
-    fn test_simple_dijkstra() {
-        let mut network = mini_instance();
-        simple_dijkstra(&mut network, 0);
-        println!("Network: {}", network);
+/// stricter alternative to `network_factory`.
+/// nodes must be declared before arcs refer to them, so that a typo in an id is reported
+/// instead of silently creating that many empty nodes.
+pub struct GraphNetworkBuilder<W = usize> {
+    network: GraphNetwork<DijkstraNode<W>, DijkstraArc<W>>,
+}
+impl<W: Weight> GraphNetworkBuilder<W> {
+    pub fn new() -> Self {
+        GraphNetworkBuilder {
+            network: empty_network(0, 0),
+        }
+    }
+    /// declare a new node. ids are given in order from 0.
+    pub fn add_node(&mut self) -> NodeId {
+        self.network.add_node(DijkstraNode {
+            distance: W::INFINITY,
+            heap_id: None,
+            predecessor: None,
+            predecessor_arc: None,
+        })
+    }
+    /// returns the id of the arc, or the first endpoint that has not been declared.
+    pub fn add_edge(
+        &mut self,
+        from: NodeId,
+        into: NodeId,
+        weight: W,
+    ) -> std::result::Result<ArcId, UndeclaredNode> {
+        for node in [from, into] {
+            if !self.network.is_node_in(node) {
+                return Err(UndeclaredNode { node });
+            }
+        }
+        Ok(self.network.connect(from, into, DijkstraArc::new(weight)))
+    }
+    pub fn build(self) -> GraphNetwork<DijkstraNode<W>, DijkstraArc<W>> {
+        self.network
+    }
+}
+impl<W: Weight> Default for GraphNetworkBuilder<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// build a network from a square matrix, where `matrix[from][into]` is the weight of the arc `from -> into`.
+/// cells equal to `no_edge` have no arc.
+/// diagonal cells are ignored, as a self-loop never shortens a path. so both `0` and `usize::MAX` can be put on the diagonal.
+pub fn from_adjacency_matrix(
+    matrix: &[Vec<usize>],
+    no_edge: usize,
+) -> GraphNetwork<DijkstraNode, DijkstraArc> {
+    let number_of_nodes = matrix.len();
+    let mut network = empty_network(number_of_nodes, 0);
+    for (from, row) in matrix.iter().enumerate() {
+        assert_eq!(row.len(), number_of_nodes, "adjacency matrix must be square");
+        for (into, &weight) in row.iter().enumerate() {
+            if from != into && weight != no_edge {
+                network.connect(from, into, DijkstraArc::new(weight));
+            }
+        }
+    }
+    network
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mini_instance() -> GraphNetwork<DijkstraNode, DijkstraArc> {
+        network_factory(vec![
+            (0, 1, 1),
+            (0, 2, 3),
+            (0, 3, 2),
+            (1, 2, 1),
+            (3, 4, 2),
+            (4, 3, 2),
+            (4, 5, 2),
+            (5, 3, 2),
+        ])
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let mut network = mini_instance();
+        dijkstra(&mut network, 0).unwrap();
+        println!("Network: \n{}", network);
+    }
+
+    #[test]
+    fn test_dijkstra_ordered_weight() {
+        let w = |value| OrderedWeight::new(value).unwrap();
+        // 0 -> 1 -> 2 -> 3 (1.35) is shorter than 0 -> 3 (1.5) and 0 -> 2 -> 3 (1.4)
+        let mut network = network_factory(vec![
+            (0, 1, w(0.5)),
+            (1, 2, w(0.25)),
+            (2, 3, w(0.6)),
+            (0, 3, w(1.5)),
+            (0, 2, w(0.8)),
+        ]);
+        dijkstra(&mut network, 0).unwrap();
+        assert_eq!(network.data_of_node(2).unwrap().distance(), w(0.75));
+        assert_eq!(network.data_of_node(3).unwrap().distance(), w(1.35));
+        assert_eq!(network.data_of_node(3).unwrap().predecessor(), Some(2));
+    }
+
+    #[test]
+    fn test_dijkstra_unreachable_nodes() {
+        let mut network = mini_instance();
+        // only 3 and 4 are reachable from 5
+        dijkstra(&mut network, 5).unwrap();
+        let mut expected = mini_instance();
+        simple_dijkstra(&mut expected, 5).unwrap();
+        assert_eq!(network.distances(), expected.distances());
+        for node_id in 0..3 {
+            let node = network.data_of_node(node_id).unwrap();
+            assert_eq!(node.distance, usize::MAX);
+            assert!(node.heap_id.is_none());
+        }
+    }
+
+    #[test]
+    fn test_reset_distances_for() {
+        let mut network = mini_instance();
+        dijkstra(&mut network, 0).unwrap();
+        reset_distances_for(&mut network, &[4, 5, 100]);
+        assert_eq!(network.distances(), vec![0, 1, 2, 2, usize::MAX, usize::MAX]);
+        assert_eq!(network.data_of_node(5).unwrap().predecessor(), None);
+        // untouched nodes keep their predecessors as well
+        assert_eq!(network.data_of_node(2).unwrap().predecessor(), Some(1));
+        assert_eq!(network.data_of_node(3).unwrap().predecessor_arc(), Some(2));
+    }
+
+    #[test]
+    fn test_invalid_start() {
+        let mut network = mini_instance();
+        assert_eq!(dijkstra(&mut network, 100), Err(DijkstraError::InvalidStart(100)));
+        assert_eq!(dijkstra_lazy(&mut network, 6), Err(DijkstraError::InvalidStart(6)));
+        assert_eq!(simple_dijkstra(&mut network, 6), Err(DijkstraError::InvalidStart(6)));
+        assert_eq!(shortest_distances(&network, 100), Err(DijkstraError::InvalidStart(100)));
+        // nothing is touched
+        assert!(network.distances().iter().all(|&distance| distance == usize::MAX));
+
+        network.remove_node(2);
+        assert_eq!(dijkstra(&mut network, 2), Err(DijkstraError::InvalidStart(2)));
+        assert_eq!(dijkstra(&mut network, 0), Ok(()));
+    }
+
+    #[test]
+    fn test_weight_near_infinity() {
+        // 1 -> 2 would reach `usize::MAX`, and 1 -> 3 would overflow, so neither arc is relaxed
+        let huge = usize::MAX - 1;
+        let arcs = vec![(0, 1, huge), (1, 2, 1), (1, 3, 5), (0, 3, 7)];
+        let expected = vec![0, huge, usize::MAX, 7];
+        let mut network = network_factory(arcs.clone());
+        simple_dijkstra(&mut network, 0).unwrap();
+        assert_eq!(network.distances(), expected);
+        assert_eq!(network.data_of_node(2).unwrap().predecessor(), None);
+        dijkstra(&mut network, 0).unwrap();
+        assert_eq!(network.distances(), expected);
+        dijkstra_lazy(&mut network, 0).unwrap();
+        assert_eq!(network.distances(), expected);
+    }
+
+    #[test]
+    fn test_simple_dijkstra_unreachable_component() {
+        // 3 -> 4 -> 5 -> 3 is not reachable from 0. relaxing from any of them at `usize::MAX` would overflow
+        let mut network = network_factory(vec![
+            (0, 1, 2),
+            (1, 2, 1),
+            (3, 4, 1),
+            (4, 5, 1),
+            (5, 3, 1),
+        ]);
+        network.add_node(DijkstraNode::default());
+        let infinity = usize::MAX;
+        simple_dijkstra(&mut network, 0).unwrap();
+        assert_eq!(network.distances(), vec![0, 2, 3, infinity, infinity, infinity, infinity]);
+        for node_id in 3..7 {
+            assert_eq!(network.data_of_node(node_id).unwrap().predecessor(), None);
+        }
+
+        simple_dijkstra(&mut network, 4).unwrap();
+        assert_eq!(network.distances(), vec![infinity, infinity, infinity, 2, 0, 1, infinity]);
+        simple_dijkstra(&mut network, 6).unwrap();
+        assert_eq!(network.distances()[6], 0);
+        assert_eq!(network.reached_distances().iter().flatten().count(), 1);
+    }
+
+    #[test]
+    fn test_dijkstra_lazy() {
+        for start_node_id in 0..6 {
+            let mut network = mini_instance();
+            dijkstra_lazy(&mut network, start_node_id).unwrap();
+            let mut expected = mini_instance();
+            dijkstra(&mut expected, start_node_id).unwrap();
+            assert_eq!(network.distances(), expected.distances());
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_with_heap() {
+        use crate::pairing_heap::PairingHeap;
+        for start_node_id in 0..6 {
+            let mut network = mini_instance();
+            dijkstra_with_heap(&mut network, start_node_id, PairingHeap::new()).unwrap();
+            let mut expected = mini_instance();
+            dijkstra(&mut expected, start_node_id).unwrap();
+            assert_eq!(network.distances(), expected.distances());
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_instrumented() {
+        let mut network = mini_instance();
+        let stats = dijkstra_instrumented(&mut network, 0).unwrap();
+        assert_eq!(stats.pops, 6);
+        assert_eq!(stats.pushes, stats.pops);
+        // 0 -> 1 -> 2 improves 2 after 0 -> 2
+        assert_eq!(stats.decrease_keys, 1);
+        // 4 -> 3 and 5 -> 3 go into 3, which is settled before 4 and 5
+        assert_eq!(stats.relaxations, 6);
+        let mut expected = mini_instance();
+        dijkstra(&mut expected, 0).unwrap();
+        assert_eq!(network.distances(), expected.distances());
+
+        // only 3, 4 and 5 are reachable from 5
+        let stats = dijkstra_instrumented(&mut network, 5).unwrap();
+        assert_eq!(stats.pops, 3);
+        // 4 -> 3 and 4 -> 5 go into settled nodes
+        assert_eq!(stats.relaxations, 2);
+
+        // 1 -> 2 overflows
+        let mut network = network_factory(vec![(0, 1, usize::MAX - 1), (1, 2, 5)]);
+        let stats = dijkstra_instrumented(&mut network, 0).unwrap();
+        assert_eq!(stats.pops, 2);
+        assert_eq!(stats.relaxations, 1);
+    }
+
+    #[test]
+    fn test_shortest_distances() {
+        let network = mini_instance();
+        assert_eq!(
+            shortest_distances(&network, 0).unwrap(),
+            vec![Some(0), Some(1), Some(2), Some(2), Some(4), Some(6)]
+        );
+        assert_eq!(
+            shortest_distances(&network, 5).unwrap(),
+            vec![None, None, None, Some(2), Some(4), Some(0)]
+        );
+        // the network itself is not modified
+        assert_eq!(network.data_of_node(0).unwrap().distance, usize::MAX);
+    }
+
+    #[test]
+    fn test_shortest_path_dag() {
+        // 0 -> 1 -> 3 and 0 -> 2 -> 3 both weigh 3
+        let network = network_factory(vec![
+            (0, 1, 1),
+            (0, 2, 2),
+            (1, 3, 2),
+            (2, 3, 1),
+            (3, 4, 1),
+            (0, 4, 5),
+        ]);
+        let dag = shortest_path_dag(&network, 0).unwrap();
+        assert_eq!(dag, vec![vec![], vec![0], vec![0], vec![1, 2], vec![3]]);
+
+        // the predecessor of the single tree is always in the dag
+        let network = mini_instance();
+        let dag = shortest_path_dag(&network, 0).unwrap();
+        let tree = shortest_path_tree(&network, 0).unwrap();
+        for node_id in 1..6 {
+            let (from, _) = network.endpoints_of_arc(tree[node_id].unwrap()).unwrap();
+            assert_eq!(dag[node_id], vec![from]);
+        }
+
+        let dag = shortest_path_dag(&network, 4).unwrap();
+        assert_eq!(dag, vec![vec![], vec![], vec![], vec![4], vec![], vec![4]]);
+        assert_eq!(shortest_path_dag(&network, 6), Err(DijkstraError::InvalidStart(6)));
+    }
+
+    #[test]
+    fn test_betweenness_centrality() {
+        // a path 0 - 1 - 2 - 3 - 4 with arcs in both directions
+        let arcs: Vec<(NodeId, NodeId, usize)> = (0..4)
+            .flat_map(|node_id| [(node_id, node_id + 1, 1), (node_id + 1, node_id, 1)])
+            .collect();
+        let network = network_factory(arcs);
+        // 2 is on the paths between {0, 1} and {3, 4}, and 1 on those between 0 and {2, 3, 4}
+        assert_eq!(betweenness_centrality(&network), vec![0.0, 6.0, 8.0, 6.0, 0.0]);
+
+        // two equally short paths from 0 to 3 share the credit
+        let network = network_factory(vec![
+            (0, 1, 1),
+            (0, 2, 2),
+            (1, 3, 2),
+            (2, 3, 1),
+            (0, 4, 1),
+            (4, 3, 4),
+        ]);
+        assert_eq!(betweenness_centrality(&network), vec![0.0, 0.5, 0.5, 0.0, 0.0]);
+
+        let mut network = mini_instance();
+        network.remove_node(2);
+        // 3 is on 0 -> 4, 0 -> 5 and 5 -> 4, and 4 on 0 -> 5 and 3 -> 5
+        assert_eq!(betweenness_centrality(&network), vec![0.0, 0.0, 0.0, 3.0, 2.0, 0.0]);
+
+        // a zero-weight arc into a larger id: 1 and 2 are at the same distance from 0, and 1 is settled first
+        let network = network_factory(vec![(0, 1, 1), (1, 2, 0), (2, 3, 1)]);
+        assert_eq!(betweenness_centrality(&network), vec![0.0, 2.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "zero-weight arc")]
+    fn test_betweenness_centrality_zero_weight_into_smaller_id() {
+        // 1 and 2 are at the same distance, and 1 is settled first though 2 is one of its predecessors
+        betweenness_centrality(&network_factory(vec![(0, 1, 1), (0, 2, 1), (2, 1, 0), (1, 3, 1)]));
+    }
+
+    #[test]
+    fn test_shortest_paths_auto() {
+        // every branch with the default thresholds: small, sparse (a ring) and dense (all the arcs)
+        let defaults = AutoThresholds::default();
+        let ring = network_factory((0..20).map(|from| (from, (from + 1) % 20, from + 1)).collect());
+        let complete = network_factory(
+            (0..20)
+                .flat_map(|from| (0..20).map(move |into| (from, into, from ^ into)))
+                .filter(|&(from, into, _)| from != into)
+                .collect(),
+        );
+        let cases = [
+            (&mini_instance(), SingleSourceAlgorithm::Simple),
+            (&ring, SingleSourceAlgorithm::Lazy),
+            (&complete, SingleSourceAlgorithm::Simple),
+        ];
+        for (network, algorithm) in cases {
+            assert_eq!(choose_algorithm(network, &defaults), algorithm);
+            for start_node_id in [0, 5, 19] {
+                if network.node_exists(start_node_id) {
+                    assert_eq!(
+                        shortest_paths_auto(network, start_node_id).unwrap(),
+                        shortest_distances(network, start_node_id).unwrap()
+                    );
+                }
+            }
+        }
+        // the complete network is chosen for its density, not its size
+        assert!(complete.node_count() > defaults.small_nodes);
+
+        // overridden thresholds
+        let network = mini_instance();
+        let expected = shortest_distances(&network, 0).unwrap();
+        // the mini instance has a density of 8 / 30
+        let large = AutoThresholds {
+            small_nodes: 0,
+            ..AutoThresholds::default()
+        };
+        assert_eq!(choose_algorithm(&network, &large), SingleSourceAlgorithm::Simple);
+        let sparse = AutoThresholds {
+            small_nodes: 0,
+            dense: 0.5,
+        };
+        assert_eq!(choose_algorithm(&network, &sparse), SingleSourceAlgorithm::Lazy);
+        assert_eq!(shortest_paths_auto_with(&network, 0, &sparse).unwrap(), expected);
+
+        for thresholds in [AutoThresholds::default(), large, sparse] {
+            for start_node_id in 0..6 {
+                assert_eq!(
+                    shortest_paths_auto_with(&network, start_node_id, &thresholds).unwrap(),
+                    shortest_distances(&network, start_node_id).unwrap()
+                );
+            }
+            let result = shortest_paths_auto_with(&network, 6, &thresholds);
+            assert_eq!(result, Err(DijkstraError::InvalidStart(6)));
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_solve() {
+        let network = mini_instance();
+        let solution = dijkstra_solve(&network, 0).unwrap();
+        assert_eq!(solution.distances, shortest_distances(&network, 0).unwrap());
+        assert_eq!(solution.path_to(0), Some(vec![0]));
+        assert_eq!(solution.path_to(2), Some(vec![0, 1, 2]));
+        assert_eq!(solution.path_to(5), Some(vec![0, 3, 4, 5]));
+        for target in 0..6 {
+            let path = solution.path_to(target).unwrap();
+            assert_eq!(network.path_weight(&path), solution.distances[target]);
+        }
+        assert_eq!(solution.path_to(100), None);
+
+        let solution = dijkstra_solve(&network, 4).unwrap();
+        assert_eq!(solution.path_to(0), None);
+        assert_eq!(solution.path_to(3), Some(vec![4, 3]));
+        assert_eq!(solution.path_to(5), Some(vec![4, 5]));
+        assert_eq!(dijkstra_solve(&network, 6), Err(DijkstraError::InvalidStart(6)));
+    }
+
+    #[test]
+    fn test_all_pairs_shortest_paths() {
+        let network = mini_instance();
+        let matrix = all_pairs_shortest_paths(&network);
+        assert_eq!(matrix.len(), 6);
+        assert_eq!(matrix[0], shortest_distances(&network, 0).unwrap());
+        assert_eq!(matrix[4], shortest_distances(&network, 4).unwrap());
+        assert_eq!(matrix[0][5], Some(6));
+        assert_eq!(matrix[3][0], None);
+        assert_eq!(matrix[5][4], Some(4));
+    }
+
+    #[test]
+    fn test_floyd_warshall() {
+        let mut network = mini_instance();
+        assert_eq!(floyd_warshall(&network), all_pairs_shortest_paths(&network));
+
+        network.remove_node(4);
+        let matrix = floyd_warshall(&network);
+        assert_eq!(matrix, all_pairs_shortest_paths(&network));
+        assert_eq!(matrix[4], vec![None; 6]);
+        assert_eq!(matrix[0][5], None);
+        assert!(floyd_warshall(&GraphNetwork::<DijkstraNode, DijkstraArc>::new()).is_empty());
+
+        // 0 -> 1 -> 2 overflows, and 0 -> 1 -> 3 reaches `usize::MAX`
+        let huge = usize::MAX - 1;
+        let network = network_factory(vec![(0, 1, huge), (1, 2, huge), (0, 2, 5), (1, 3, 1)]);
+        let matrix = floyd_warshall(&network);
+        assert_eq!(matrix, all_pairs_shortest_paths(&network));
+        assert_eq!(matrix[0], vec![Some(0), Some(huge), Some(5), None]);
+    }
+
+    #[test]
+    fn test_eccentricity_and_diameter() {
+        let network = mini_instance();
+        // 0 reaches 5 at 6, 3 reaches 5 at 4 (via 4), 5 reaches 4 at 4 (via 3), and 2 reaches nothing
+        let eccentricities: Vec<Option<usize>> =
+            (0..6).map(|node_id| eccentricity(&network, node_id)).collect();
+        assert_eq!(eccentricities, vec![Some(6), Some(1), Some(0), Some(4), Some(2), Some(4)]);
+        assert_eq!(eccentricity(&network, 100), None);
+        assert_eq!(diameter(&network), Some(6));
+        assert_eq!(diameter(&GraphNetwork::<DijkstraNode, DijkstraArc>::new()), None);
+    }
+
+    #[test]
+    fn test_incremental_construction() {
+        let mut network = GraphNetwork::<DijkstraNode, DijkstraArc>::new();
+        let mut previous = network.add_node(DijkstraNode::default());
+        let start = previous;
+        for weight in 1..5 {
+            let node_id = network.add_node(DijkstraNode::default());
+            network.connect(previous, node_id, DijkstraArc::new(weight));
+            previous = node_id;
+        }
+        dijkstra(&mut network, start).unwrap();
+        assert_eq!(network.distances(), vec![0, 1, 3, 6, 10]);
+    }
+
+    #[test]
+    fn test_reset_distances() {
+        let mut network = mini_instance();
+        dijkstra(&mut network, 0).unwrap();
+        reset_distances(&mut network);
+        assert!(network.distances().iter().all(|&distance| distance == usize::MAX));
+        assert!(network.node_data.iter().flatten().all(|node| node.predecessor.is_none()));
+
+        // a second run from another start on the same network
+        dijkstra(&mut network, 0).unwrap();
+        dijkstra(&mut network, 4).unwrap();
+        assert_eq!(network.distances(), vec![usize::MAX, usize::MAX, usize::MAX, 2, 0, 2]);
+        assert_eq!(network.data_of_node(1).unwrap().predecessor(), None);
+        simple_dijkstra(&mut network, 3).unwrap();
+        assert_eq!(network.distances(), vec![usize::MAX, usize::MAX, usize::MAX, 0, 2, 4]);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        // empty arc list gives no nodes
+        let network = network_factory::<usize>(vec![]);
+        assert_eq!(network.node_count(), 0);
+        assert!(all_pairs_shortest_paths(&network).is_empty());
+
+        // single isolated node as start
+        let mut builder = GraphNetworkBuilder::<usize>::new();
+        let only = builder.add_node();
+        let mut network = builder.build();
+        dijkstra(&mut network, only).unwrap();
+        assert_eq!(network.distances(), vec![0]);
+        simple_dijkstra(&mut network, only).unwrap();
+        assert_eq!(network.distances(), vec![0]);
+
+        // start node with no outgoing arcs
+        let mut network = mini_instance();
+        dijkstra(&mut network, 2).unwrap();
+        let mut expected = vec![usize::MAX; 6];
+        expected[2] = 0;
+        assert_eq!(network.distances(), expected);
+        dijkstra_lazy(&mut network, 2).unwrap();
+        assert_eq!(network.distances(), expected);
+    }
+
+    #[test]
+    fn test_shortest_path_tree() {
+        let network = mini_instance();
+        let tree = shortest_path_tree(&network, 0).unwrap();
+        let distances = shortest_distances(&network, 0).unwrap();
+        assert_eq!(tree[0], None);
+        for (node_id, distance) in distances.iter().enumerate().skip(1) {
+            // every reachable node has its arc, and following the arcs from it ends up at the root
+            let mut current = node_id;
+            let mut steps = 0;
+            let mut length = 0;
+            while let Some(arc_id) = tree[current] {
+                let (_, from, into, arc) = network.iter_arcs().find(|&(id, ..)| id == arc_id).unwrap();
+                assert_eq!(into, current);
+                length += arc.weight();
+                current = from;
+                steps += 1;
+                assert!(steps < 6, "the tree has a cycle");
+            }
+            assert_eq!(current, 0);
+            assert_eq!(Some(length), *distance);
+        }
+        // unreachable nodes are not in the tree
+        assert_eq!(shortest_path_tree(&network, 5).unwrap()[..3], [None, None, None]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_all_sources() {
+        let network = mini_instance();
+        let sources = [0, 3, 5, 0, 2];
+        let sequential: Vec<Vec<Option<usize>>> = sources
+            .iter()
+            .map(|&source| shortest_distances(&network, source).unwrap())
+            .collect();
+        assert_eq!(parallel_all_sources(&network, &sources).unwrap(), sequential);
+    }
+
+    #[test]
+    fn test_map_arcs() {
+        let network = mini_instance();
+        let doubled = network.map_arcs(|arc| DijkstraArc::new(arc.weight() * 2));
+        assert_eq!(doubled.arc_count(), network.arc_count());
+        let expected: Vec<Option<usize>> = shortest_distances(&network, 0).unwrap()
+            .into_iter()
+            .map(|distance| distance.map(|distance| distance * 2))
+            .collect();
+        assert_eq!(shortest_distances(&doubled, 0).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_dijkstra_with_callback() {
+        let mut network = mini_instance();
+        let mut settled = Vec::new();
+        let result = dijkstra_with_callback(&mut network, 0, |node_id, distance| {
+            settled.push((node_id, distance));
+            ControlFlow::Continue(())
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(settled.len(), 6);
+        for &(node_id, distance) in settled.iter() {
+            assert_eq!(network.data_of_node(node_id).unwrap().distance, distance);
+        }
+
+        // break right after the start node
+        let mut count = 0;
+        let result = dijkstra_with_callback(&mut network, 0, |_, _| {
+            count += 1;
+            ControlFlow::Break(())
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(count, 1);
+        assert_eq!(network.data_of_node(0).unwrap().distance, 0);
+        // nothing is relaxed from the start node
+        assert_eq!(network.data_of_node(1).unwrap().distance, usize::MAX);
+    }
+
+    #[test]
+    fn test_dijkstra_settle_order() {
+        let mut network = mini_instance();
+        let order = dijkstra_settle_order(&mut network, 0).unwrap();
+        assert_eq!(order.len(), 6);
+        assert_eq!(order[0], 0);
+        let distances = network.distances();
+        assert!(order.windows(2).all(|pair| distances[pair[0]] <= distances[pair[1]]));
+
+        // 0, 1 and 2 are unreachable from 3
+        let order = dijkstra_settle_order(&mut network, 3).unwrap();
+        assert_eq!(order, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_settle_order_tie_breaking() {
+        use crate::pairing_heap::PairingHeap;
+
+        // 1, 2 and 3 are all at 1, and 4 is at 2 through any of them
+        let arcs = vec![(0, 3, 1), (0, 2, 1), (0, 1, 1), (3, 4, 1), (2, 4, 1), (1, 4, 1)];
+        let mut network = network_factory(arcs);
+        assert_eq!(dijkstra_settle_order(&mut network, 0).unwrap(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(network.data_of_node(4).unwrap().predecessor(), Some(1));
+
+        // the same with the other heaps
+        let mut order = Vec::new();
+        let mut heap = PairingHeap::new();
+        search(&mut network, 0, &mut heap, |node_id, _| {
+            order.push(node_id);
+            ControlFlow::Continue(())
+        });
+        assert_eq!(order, vec![0, 1, 2, 3, 4]);
+        assert_eq!(network.data_of_node(4).unwrap().predecessor(), Some(1));
+        dijkstra_lazy(&mut network, 0).unwrap();
+        assert_eq!(network.data_of_node(4).unwrap().predecessor(), Some(1));
+    }
+
+    #[test]
+    fn test_dijkstra_seeded() {
+        let mut network = mini_instance();
+        // 1 starts at 5 and 4 at 1. 3 is at 3 (4 -> 3), 5 at 3 (4 -> 5), and 2 at 6 (1 -> 2)
+        dijkstra_seeded(&mut network, &[(1, 5), (4, 1), (4, 7)]).unwrap();
+        assert_eq!(network.distances(), vec![usize::MAX, 5, 6, 3, 1, 3]);
+        assert_eq!(network.data_of_node(4).unwrap().predecessor(), None);
+        assert_eq!(network.data_of_node(3).unwrap().predecessor(), Some(4));
+
+        // a seed further than the paths from the others is just relaxed through
+        dijkstra_seeded(&mut network, &[(0, 0), (5, 10)]).unwrap();
+        let mut expected = mini_instance();
+        dijkstra(&mut expected, 0).unwrap();
+        assert_eq!(network.distances(), expected.distances());
+
+        // a single seed at zero is plain dijkstra
+        dijkstra_seeded(&mut network, &[(3, 0)]).unwrap();
+        dijkstra(&mut expected, 3).unwrap();
+        assert_eq!(network.distances(), expected.distances());
+
+        let result = dijkstra_seeded(&mut network, &[(0, 0), (9, 0)]);
+        assert_eq!(result, Err(DijkstraError::InvalidStart(9)));
+        assert_eq!(network.distances(), expected.distances());
+    }
+
+    #[test]
+    fn test_dijkstra_to_any() {
+        let mut network = mini_instance();
+        let targets = HashSet::from([2, 4]);
+        assert_eq!(dijkstra_to_any(&mut network, 0, &targets).unwrap(), Some((2, 2)));
+        assert_eq!(dijkstra_to_any(&mut network, 3, &targets).unwrap(), Some((4, 2)));
+        // the start node is a target
+        assert_eq!(dijkstra_to_any(&mut network, 4, &targets).unwrap(), Some((4, 0)));
+        // unreachable
+        assert_eq!(dijkstra_to_any(&mut network, 5, &HashSet::from([0, 1])).unwrap(), None);
+    }
+
+    #[test]
+    fn test_dijkstra_update() {
+        // (arc, new weight) in order. decreases and increases, on and off the tree
+        let changes = [(1, 1), (0, 5), (2, 10), (4, 1), (3, 0), (6, 7), (2, 2), (0, 1)];
+        let mut network = mini_instance();
+        dijkstra(&mut network, 0).unwrap();
+        for (arc, new_weight) in changes {
+            update_arc_weight(&mut network, arc, new_weight);
+            dijkstra_update(&mut network, arc);
+
+            let mut expected = network.clone();
+            dijkstra(&mut expected, 0).unwrap();
+            assert_eq!(network.distances(), expected.distances(), "after arc {}", arc);
+            // predecessors may differ on ties, but they must be consistent with the distances
+            for (node_id, node) in network.iter_nodes() {
+                if let Some(arc_id) = node.predecessor_arc() {
+                    let (from, into) = network.endpoints_of_arc(arc_id).unwrap();
+                    assert_eq!(into, node_id);
+                    assert_eq!(Some(from), node.predecessor());
+                    let weight = network.data_of_arc(arc_id).unwrap().weight();
+                    assert_eq!(network.data_of_node(from).unwrap().distance + weight, node.distance);
+                }
+            }
+        }
+        assert_eq!(update_arc_weight(&mut network, 100, 1), None);
+    }
+
+    #[test]
+    fn test_dijkstra_update_near_infinity() {
+        // 0 -> 1 becomes huge, so that 1 -> 2 overflows or reaches `usize::MAX` through it
+        let huge = usize::MAX - 1;
+        let changes = [(0, huge), (3, 5), (3, 1), (1, huge - 1), (1, 3), (0, 1)];
+        let mut network = mini_instance();
+        dijkstra(&mut network, 0).unwrap();
+        for (arc, new_weight) in changes {
+            update_arc_weight(&mut network, arc, new_weight);
+            dijkstra_update(&mut network, arc);
+
+            let mut expected = network.clone();
+            dijkstra(&mut expected, 0).unwrap();
+            assert_eq!(network.distances(), expected.distances(), "after arc {}", arc);
+        }
+        assert_eq!(network.distances(), vec![0, 1, 2, 2, 4, 6]);
+    }
+
+    #[test]
+    fn test_simple_dijkstra() {
+        let mut network = mini_instance();
+        simple_dijkstra(&mut network, 0).unwrap();
+        println!("Network: {}", network);
+    }
+
+    #[test]
+    fn test_dijkstra_after_remove_node() {
+        let mut network = mini_instance();
+        dijkstra(&mut network, 0).unwrap();
+        assert_eq!(network.data_of_node(2).unwrap().distance, 2);
+        assert_eq!(network.data_of_node(5).unwrap().distance, 6);
+
+        // 1 is on the shortest path to 2, and 4 is the only way to 5
+        network.remove_node(1);
+        network.remove_node(4);
+        assert_eq!(
+            network.from_node(0).map(|(into, _)| into).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(network.from_node(3).count(), 0);
+
+        dijkstra(&mut network, 0).unwrap();
+        assert!(network.data_of_node(1).is_none());
+        assert_eq!(network.data_of_node(2).unwrap().distance, 3);
+        assert_eq!(network.data_of_node(3).unwrap().distance, 2);
+        assert_eq!(network.data_of_node(5).unwrap().distance, usize::MAX);
+
+        simple_dijkstra(&mut network, 0).unwrap();
+        assert_eq!(network.data_of_node(2).unwrap().distance, 3);
+        assert_eq!(network.data_of_node(5).unwrap().distance, usize::MAX);
+    }
+
+    #[test]
+    fn test_contract_node() {
+        let original = mini_instance();
+        let mut contracted = mini_instance();
+        // 4 is the only way to 5, and 1 is on the shortest path to 2
+        contracted.contract_node(4);
+        contracted.contract_node(1);
+        assert!(!contracted.node_exists(4) && !contracted.node_exists(1));
+        // 3 -> 5 is a new shortcut, 0 -> 2 is lowered from 3 to 2
+        assert!(contracted.has_edge(3, 5));
+        let arc_0_2 = contracted.between_nodes(0, 2).next().unwrap();
+        assert_eq!(contracted.data_of_arc(arc_0_2).unwrap().weight(), 2);
+
+        for start_node_id in [0, 2, 3, 5] {
+            let expected = shortest_distances(&original, start_node_id).unwrap();
+            let distances = shortest_distances(&contracted, start_node_id).unwrap();
+            for node_id in [0, 2, 3, 5] {
+                assert_eq!(distances[node_id], expected[node_id]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_contract_node_with_witness() {
+        // 0 -> 1 -> 2 costs 4, but 0 -> 3 -> 2 costs 2
+        let arcs = vec![(0, 1, 2), (1, 2, 2), (0, 3, 1), (3, 2, 1)];
+        let original = network_factory(arcs.clone());
+        let mut contracted = network_factory(arcs);
+        contracted.contract_node(1);
+        assert!(!contracted.has_edge(0, 2));
+        assert_eq!(contracted.arc_count(), 2);
+        for start_node_id in [0, 2, 3] {
+            let expected = shortest_distances(&original, start_node_id).unwrap();
+            let distances = shortest_distances(&contracted, start_node_id).unwrap();
+            for node_id in [0, 2, 3] {
+                assert_eq!(distances[node_id], expected[node_id]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_contract_node_near_infinity() {
+        // the witness search from 0 overflows on 3 -> 4, and the shortcut 0 -> 5 overflows
+        let huge = usize::MAX - 1;
+        let arcs = vec![(0, 1, 1), (1, 2, huge - 1), (0, 3, huge - 2), (3, 4, 5), (1, 5, huge)];
+        let original = network_factory(arcs.clone());
+        let mut contracted = network_factory(arcs);
+        contracted.contract_node(1);
+        assert!(contracted.has_edge(0, 2));
+        assert!(!contracted.has_edge(0, 5));
+        for start_node_id in [0, 2, 3, 4, 5] {
+            let expected = shortest_distances(&original, start_node_id).unwrap();
+            let distances = shortest_distances(&contracted, start_node_id).unwrap();
+            for node_id in [0, 2, 3, 4, 5] {
+                assert_eq!(distances[node_id], expected[node_id]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_union() {
+        let mut network = mini_instance();
+        // 0 -> 1 -> 2, weights 1 and 4
+        let fragment = network_factory(vec![(0, 1, 1), (1, 2, 4)]);
+        let offset = network.union(fragment);
+        assert_eq!(offset, 6);
+        assert_eq!(network.node_count(), 9);
+        assert_eq!(network.arc_count(), 10);
+        assert_eq!(network.endpoints_of_arc(9), Some((offset + 1, offset + 2)));
+
+        dijkstra(&mut network, 0).unwrap();
+        assert_eq!(network.data_of_node(offset).unwrap().distance, usize::MAX);
+
+        // across the seam
+        network.connect(5, offset, DijkstraArc::new(1));
+        dijkstra(&mut network, 0).unwrap();
+        let distances: Vec<usize> = (offset..offset + 3)
+            .map(|node_id| network.data_of_node(node_id).unwrap().distance)
+            .collect();
+        assert_eq!(distances, vec![7, 8, 12]);
+    }
+
+    #[test]
+    fn test_clone_with_default_data() {
+        let mut network = mini_instance();
+        network.remove_node(2);
+        dijkstra(&mut network, 0).unwrap();
+        let mut fresh = network.clone_with_default_data();
+        assert!(fresh.iter_nodes().all(|(_, node)| node.distance == usize::MAX));
+        assert!(fresh.iter_nodes().all(|(_, node)| node.predecessor.is_none()));
+        assert!(!fresh.node_exists(2));
+        let arcs = |network: &GraphNetwork<DijkstraNode, DijkstraArc>| {
+            network
+                .iter_arcs()
+                .map(|(arc_id, from, into, arc)| (arc_id, from, into, arc.weight))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(arcs(&fresh), arcs(&network));
+
+        dijkstra(&mut fresh, 0).unwrap();
+        assert_eq!(fresh.distances(), network.distances());
+    }
+
+    #[test]
+    fn test_path_weight() {
+        let mut network = mini_instance();
+        assert_eq!(network.total_arc_weight(), 15);
+        dijkstra(&mut network, 0).unwrap();
+
+        // follow the predecessors back from 5
+        let mut path = vec![5];
+        while let Some(predecessor) = network.data_of_node(*path.last().unwrap()).unwrap().predecessor {
+            path.push(predecessor);
+        }
+        path.reverse();
+        assert_eq!(path, vec![0, 3, 4, 5]);
+        assert_eq!(network.path_weight(&path), Some(network.data_of_node(5).unwrap().distance));
+
+        assert_eq!(network.path_weight(&[0, 2]), Some(3));
+        assert_eq!(network.path_weight(&[2]), Some(0));
+        assert_eq!(network.path_weight(&[0, 5]), None);
+        assert_eq!(network.path_weight(&[0, 100]), None);
+    }
+
+    #[test]
+    fn test_getters() {
+        let mut network = mini_instance();
+        dijkstra(&mut network, 0).unwrap();
+        assert_eq!(network.distances(), vec![0, 1, 2, 2, 4, 6]);
+        assert_eq!(network.data_of_node(5).unwrap().distance(), 6);
+        assert_eq!(network.data_of_node(5).unwrap().predecessor(), Some(4));
+        assert_eq!(network.data_of_node(0).unwrap().predecessor(), None);
+        assert_eq!(network.data_of_arc(1).unwrap().weight(), 3);
+
+        network.remove_node(1);
+        dijkstra(&mut network, 0).unwrap();
+        assert_eq!(network.distances(), vec![0, usize::MAX, 3, 2, 4, 6]);
+    }
+
+    #[test]
+    fn test_dag_shortest_paths() {
+        let mut network = mini_instance();
+        assert!(matches!(dag_shortest_paths(&mut network, 0), Err(DagError::Cycle(_))));
+        assert_eq!(dag_shortest_paths(&mut network, 6), Err(DagError::InvalidStart(6)));
+
+        // remove 4 -> 3 and 5 -> 3 to break the cycles
+        network.disconnect(5);
+        network.disconnect(7);
+        dag_shortest_paths(&mut network, 0).unwrap();
+        let dag_distances: Vec<usize> = (0..6)
+            .map(|node_id| network.data_of_node(node_id).unwrap().distance)
+            .collect();
+        dijkstra(&mut network, 0).unwrap();
+        let distances: Vec<usize> = (0..6)
+            .map(|node_id| network.data_of_node(node_id).unwrap().distance)
+            .collect();
+        assert_eq!(dag_distances, distances);
+        assert_eq!(dag_distances, vec![0, 1, 2, 2, 4, 6]);
+
+        dag_shortest_paths(&mut network, 3).unwrap();
+        assert_eq!(network.data_of_node(0).unwrap().distance, usize::MAX);
+        assert_eq!(network.data_of_node(5).unwrap().distance, 4);
+
+        network.remove_node(1);
+        assert_eq!(dag_shortest_paths(&mut network, 1), Err(DagError::InvalidStart(1)));
+    }
+
+    #[test]
+    fn test_validate_weights() {
+        let mut network = network_factory::<i64>(vec![(0, 1, 1), (1, 2, 3), (0, 2, 5)]);
+        assert_eq!(validate_weights(&network), Ok(()));
+        // a zero weight is fine
+        network.connect(2, 0, DijkstraArc::new(0));
+        assert_eq!(validate_weights(&network), Ok(()));
+        let arc_id = network.connect(2, 1, DijkstraArc::new(-1));
+        assert_eq!(validate_weights(&network), Err(arc_id));
+        network.disconnect(arc_id);
+        assert_eq!(validate_weights(&network), Ok(()));
+    }
+
+    #[test]
+    fn test_network_from_edges() {
+        let arcs = vec![
+            (0, 1, 1),
+            (0, 2, 3),
+            (0, 3, 2),
+            (1, 2, 1),
+            (3, 4, 2),
+            (4, 3, 2),
+            (4, 5, 2),
+            (5, 3, 2),
+        ];
+        // a lazy adapter, as if parsed from the lines of a file
+        let lines = arcs.iter().map(|(from, into, weight)| format!("{} {} {}", from, into, weight));
+        let edges = lines.map(|line| {
+            let fields: Vec<usize> = line.split(' ').map(|field| field.parse().unwrap()).collect();
+            (fields[0], fields[1], fields[2])
+        });
+        let mut network = network_from_edges(edges, 7);
+        let mut expected = network_factory(arcs);
+        assert_eq!(network.node_count(), 7);
+        assert_eq!(network.arc_count(), expected.arc_count());
+        for node_id in 0..6 {
+            assert_eq!(
+                network.from_node(node_id).collect::<Vec<_>>(),
+                expected.from_node(node_id).collect::<Vec<_>>()
+            );
+        }
+        dijkstra(&mut network, 0).unwrap();
+        dijkstra(&mut expected, 0).unwrap();
+        assert_eq!(network.distances()[..6], expected.distances()[..]);
+        // the isolated node
+        assert_eq!(network.distances()[6], usize::MAX);
+    }
+
+    #[test]
+    fn test_to_edge_list() {
+        let mut network = mini_instance();
+        let edges: Vec<(NodeId, NodeId, usize)> = network
+            .to_edge_list()
+            .into_iter()
+            .map(|(from, into, arc)| (from, into, arc.weight()))
+            .collect();
+        assert_eq!(edges[..2], [(0, 1, 1), (0, 2, 3)]);
+        let mut rebuilt = network_factory(edges);
+        assert_eq!(rebuilt.to_edge_list().len(), 8);
+        dijkstra(&mut network, 0).unwrap();
+        dijkstra(&mut rebuilt, 0).unwrap();
+        assert_eq!(network.distances(), rebuilt.distances());
+
+        // the arcs of a removed node are not exported
+        network.remove_node(1);
+        let edges: Vec<(NodeId, NodeId, usize)> = network
+            .to_edge_list()
+            .into_iter()
+            .map(|(from, into, arc)| (from, into, arc.weight()))
+            .collect();
+        assert_eq!(edges.len(), 6);
+        assert!(edges.iter().all(|&(from, into, _)| from != 1 && into != 1));
+        let mut rebuilt = network_factory(edges);
+        dijkstra(&mut network, 0).unwrap();
+        dijkstra(&mut rebuilt, 0).unwrap();
+        assert_eq!(network.distances(), rebuilt.distances());
+    }
+
+    #[test]
+    fn test_permute_nodes() {
+        let mut network = mini_instance();
+        network.remove_node(2);
+        let reversal: Vec<NodeId> = (0..6).rev().collect();
+        let mut permuted = network.clone();
+        permuted.permute_nodes(&reversal);
+        assert_eq!(permuted.validate(), Ok(()));
+        assert!(!permuted.is_node_in(reversal[2]));
+        // arc 0 is still 0 -> 1
+        assert_eq!(permuted.endpoints_of_arc(0), Some((5, 4)));
+
+        for start_node_id in [0, 1, 3, 4, 5] {
+            let expected = shortest_distances(&network, start_node_id).unwrap();
+            let distances = shortest_distances(&permuted, reversal[start_node_id]).unwrap();
+            for node_id in 0..6 {
+                assert_eq!(distances[reversal[node_id]], expected[node_id]);
+            }
+        }
+
+        // back to the original ids by the inverse, which is the reversal itself
+        permuted.permute_nodes(&reversal);
+        for arc_id in 0..8 {
+            assert_eq!(permuted.endpoints_of_arc(arc_id), network.endpoints_of_arc(arc_id));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not a permutation")]
+    fn test_permute_nodes_invalid() {
+        mini_instance().permute_nodes(&[0, 1, 2, 3, 4, 4]);
+    }
+
+    #[test]
+    fn test_network_factory_dedup() {
+        let arcs = vec![(0, 1, 5), (0, 1, 1), (1, 2, 1), (0, 2, 3), (1, 2, 4), (0, 1, 2)];
+        let mut network = network_factory(arcs.clone());
+        let mut deduped = network_factory_dedup(arcs);
+        assert_eq!(network.arc_count(), 6);
+        assert_eq!(deduped.arc_count(), 3);
+        let kept: Vec<(NodeId, NodeId, usize)> = deduped
+            .iter_arcs()
+            .map(|(_, from, into, arc)| (from, into, arc.weight()))
+            .collect();
+        assert_eq!(kept, vec![(0, 1, 1), (1, 2, 1), (0, 2, 3)]);
+        dijkstra(&mut network, 0).unwrap();
+        dijkstra(&mut deduped, 0).unwrap();
+        assert_eq!(network.distances(), deduped.distances());
+    }
+
+    #[test]
+    fn test_remove_self_loops() {
+        let mut network = mini_instance();
+        network.connect(3, 3, DijkstraArc::new(1));
+        network.connect(0, 0, DijkstraArc::new(0));
+        let before = shortest_distances(&network, 0).unwrap();
+        assert_eq!(network.arc_count(), 10);
+
+        assert_eq!(network.remove_self_loops(), 2);
+        assert_eq!(network.arc_count(), 8);
+        assert!(!network.has_edge(3, 3));
+        assert_eq!(shortest_distances(&network, 0).unwrap(), before);
+        assert_eq!(network.remove_self_loops(), 0);
+    }
+
+    #[test]
+    fn test_subgraph() {
+        let mut network = mini_instance();
+        let (mut subgraph, mapping) = network.subgraph(&HashSet::from([5, 3, 4]));
+        assert_eq!(mapping, vec![3, 4, 5]);
+        assert_eq!(subgraph.node_count(), 3);
+        // 3 -> 4, 4 -> 3, 4 -> 5 and 5 -> 3
+        assert_eq!(subgraph.arc_count(), 4);
+        assert!(subgraph.has_edge(2, 0));
+
+        // every shortest path from 3 stays in {3, 4, 5}
+        dijkstra(&mut network, 3).unwrap();
+        dijkstra(&mut subgraph, 0).unwrap();
+        for (new_id, &old_id) in mapping.iter().enumerate() {
+            assert_eq!(
+                subgraph.data_of_node(new_id).unwrap().distance,
+                network.data_of_node(old_id).unwrap().distance
+            );
+        }
+    }
+
+    #[test]
+    fn test_builder() {
+        let mut builder = GraphNetworkBuilder::new();
+        for _ in 0..6 {
+            builder.add_node();
+        }
+        for (from, into, weight) in [
+            (0, 1, 1),
+            (0, 2, 3),
+            (0, 3, 2),
+            (1, 2, 1),
+            (3, 4, 2),
+            (4, 3, 2),
+            (4, 5, 2),
+            (5, 3, 2),
+        ] {
+            builder.add_edge(from, into, weight).unwrap();
+        }
+        let mut network = builder.build();
+        dijkstra(&mut network, 0).unwrap();
+        let mut expected = mini_instance();
+        dijkstra(&mut expected, 0).unwrap();
+        assert_eq!(network.distances(), expected.distances());
+    }
+
+    #[test]
+    fn test_builder_undeclared_node() {
+        let mut builder = GraphNetworkBuilder::<usize>::new();
+        builder.add_node();
+        builder.add_node();
+        assert_eq!(builder.add_edge(0, 1, 1), Ok(0));
+        // typo'd id
+        assert_eq!(builder.add_edge(0, 10, 1), Err(UndeclaredNode { node: 10 }));
+        assert_eq!(builder.add_edge(7, 1, 1), Err(UndeclaredNode { node: 7 }));
+        let network = builder.build();
+        assert_eq!(network.node_data.len(), 2);
+        assert_eq!(network.from_node(0).count(), 1);
+    }
+
+    #[test]
+    fn test_from_adjacency_matrix() {
+        let no_edge = usize::MAX;
+        let mut network = from_adjacency_matrix(
+            &[
+                vec![0, 4, 1, no_edge],
+                vec![no_edge, 0, no_edge, 1],
+                vec![no_edge, 2, 0, 5],
+                vec![no_edge, no_edge, no_edge, 0],
+            ],
+            no_edge,
+        );
+        assert_eq!(network.from_node(0).count(), 2);
+        assert!(!network.is_arc_in(1, 1));
+        dijkstra(&mut network, 0).unwrap();
+        let distances: Vec<usize> = (0..4)
+            .map(|node_id| network.data_of_node(node_id).unwrap().distance)
+            .collect();
+        assert_eq!(distances, vec![0, 3, 1, 4]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut network = mini_instance();
+        let json = serde_json::to_string(&network).unwrap();
+        let mut restored: GraphNetwork<DijkstraNode, DijkstraArc> =
+            serde_json::from_str(&json).unwrap();
+        for node_id in 0..6 {
+            assert_eq!(
+                network.from_node(node_id).collect::<Vec<_>>(),
+                restored.from_node(node_id).collect::<Vec<_>>()
+            );
+        }
+        dijkstra(&mut network, 0).unwrap();
+        dijkstra(&mut restored, 0).unwrap();
+        for node_id in 0..6 {
+            assert_eq!(
+                network.data_of_node(node_id).unwrap().distance,
+                restored.data_of_node(node_id).unwrap().distance
+            );
+        }
     }
 }
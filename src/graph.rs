@@ -1,25 +1,90 @@
 use genawaiter::sync::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter, Result};
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 pub type NodeId = usize;
 pub type ArcId = usize;
 
+// ids are stored in the adjacency and the arc connections as `StoredId`. it is `u32` with the `compact-ids`
+// feature, which halves the memory of them on a large network (e.g. a continent-scale road network).
+// the api always takes and returns `usize` ids, and they are converted at the boundary.
+#[cfg(feature = "compact-ids")]
+type StoredId = u32;
+#[cfg(not(feature = "compact-ids"))]
+type StoredId = usize;
+
+/// panics if the id does not fit in `StoredId`, i.e. beyond `u32::MAX` with `compact-ids`
+#[cfg(feature = "compact-ids")]
+fn store(id: usize) -> StoredId {
+    StoredId::try_from(id).expect("id does not fit in u32 (the compact-ids feature is enabled)")
+}
+#[cfg(feature = "compact-ids")]
+fn load(id: StoredId) -> usize {
+    id as usize
+}
+#[cfg(not(feature = "compact-ids"))]
+fn store(id: usize) -> StoredId {
+    id
+}
+#[cfg(not(feature = "compact-ids"))]
+fn load(id: StoredId) -> usize {
+    id
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct ArcConnection {
-    from: NodeId,
-    into: NodeId,
+    from: StoredId,
+    into: StoredId,
+}
+impl ArcConnection {
+    fn new(from: NodeId, into: NodeId) -> Self {
+        ArcConnection {
+            from: store(from),
+            into: store(into),
+        }
+    }
+    /// `(from, into)`
+    fn ends(&self) -> (NodeId, NodeId) {
+        (load(self.from), load(self.into))
+    }
 }
 
-#[derive(Debug)]
+/// the graph is not acyclic. `node` is on a cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    pub node: NodeId,
+}
+impl Display for CycleError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "node {} is on a cycle", self.node)
+    }
+}
+impl std::error::Error for CycleError {}
+
+/// density from which `GraphNetwork::is_dense` holds. a network with a quarter of all the possible arcs
+/// has Θ(V²) arcs, so the dense algorithms (e.g. `floyd_warshall`) lose nothing by scanning every pair.
+pub const DENSE_THRESHOLD: f64 = 0.25;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GraphNetwork<N, A> {
-    pub node_data: Vec<Option<N>>, // Option is to support removal of nodes
-    arcs_into: Vec<Vec<ArcId>>,    // The length of this vector is the number of nodes
-    arcs_from: Vec<Vec<ArcId>>,    // The length of this vector is the number of nodes
-    pub arc_data: Vec<Option<A>>,  // Option is to support removal of arcs
+    pub(crate) node_data: Vec<Option<N>>, // Option is to support removal of nodes
+    arcs_into: Vec<Vec<StoredId>>, // The length of this vector is the number of nodes
+    arcs_from: Vec<Vec<StoredId>>, // The length of this vector is the number of nodes
+    pub(crate) arc_data: Vec<Option<A>>, // Option is to support removal of arcs
     arc_connections: Vec<ArcConnection>, // The length of this vector is the number of arcs
 }
 
+impl<N, A> Default for GraphNetwork<N, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'g, N, A> GraphNetwork<N, A> {
     pub fn new() -> Self {
         GraphNetwork {
@@ -31,6 +96,19 @@ impl<'g, N, A> GraphNetwork<N, A> {
         }
     }
 
+    /// empty network with room for `number_of_nodes` nodes and `number_of_arcs` arcs,
+    /// so that building a large network does not reallocate on the way.
+    /// the adjacency of each node still grows on demand, as the degrees are not known in advance.
+    pub fn with_capacity(number_of_nodes: usize, number_of_arcs: usize) -> Self {
+        GraphNetwork {
+            node_data: Vec::with_capacity(number_of_nodes),
+            arcs_into: Vec::with_capacity(number_of_nodes),
+            arcs_from: Vec::with_capacity(number_of_nodes),
+            arc_data: Vec::with_capacity(number_of_arcs),
+            arc_connections: Vec::with_capacity(number_of_arcs),
+        }
+    }
+
     pub fn clean(self) -> Self {
         let mut old_new_map = HashMap::<NodeId, NodeId>::new();
         let mut brand_new = Self::new();
@@ -44,18 +122,70 @@ impl<'g, N, A> GraphNetwork<N, A> {
 
         for (old_arc_id, arc_data) in self.arc_data.into_iter().enumerate() {
             if let Some(arc_data) = arc_data {
-                let ArcConnection { from, into } = self.arc_connections[old_arc_id];
+                let (from, into) = self.arc_connections[old_arc_id].ends();
                 brand_new.connect(old_new_map[&from], old_new_map[&into], arc_data);
             }
         }
 
-        return brand_new;
+        brand_new
     }
 
     pub fn is_node_in(&self, node: NodeId) -> bool {
         self.node_data.len() > node && self.node_data[node].is_some()
     }
 
+    /// same as `is_arc_in`.
+    pub fn has_edge(&self, from: NodeId, into: NodeId) -> bool {
+        self.is_arc_in(from, into)
+    }
+
+    /// number of nodes, not counting removed ones
+    pub fn node_count(&self) -> usize {
+        self.node_data.iter().flatten().count()
+    }
+
+    /// number of arcs, not counting disconnected ones
+    pub fn arc_count(&self) -> usize {
+        self.arc_data.iter().flatten().count()
+    }
+
+    /// arcs per possible arc, `arc_count / (node_count · (node_count - 1))`. 0 with less than two nodes.
+    /// self-loops and parallel arcs are counted as well, so it may exceed 1.
+    pub fn density(&self) -> f64 {
+        let number_of_nodes = self.node_count() as f64;
+        if number_of_nodes < 2.0 {
+            return 0.0;
+        }
+        self.arc_count() as f64 / (number_of_nodes * (number_of_nodes - 1.0))
+    }
+
+    /// true if `density` is at least `DENSE_THRESHOLD`
+    pub fn is_dense(&self) -> bool {
+        self.density() >= DENSE_THRESHOLD
+    }
+
+    /// average out-degree, i.e. arcs per node. 0 without nodes.
+    pub fn average_degree(&self) -> f64 {
+        match self.node_count() {
+            0 => 0.0,
+            number_of_nodes => self.arc_count() as f64 / number_of_nodes as f64,
+        }
+    }
+
+    /// number of nodes of each out-degree. the counts sum up to `node_count`.
+    pub fn degree_histogram(&self) -> HashMap<usize, usize> {
+        let mut histogram = HashMap::new();
+        for (node_id, _) in self.iter_nodes() {
+            *histogram.entry(self.out_degree(node_id)).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    /// same as `is_node_in`. false for both out-of-range and removed ids.
+    pub fn node_exists(&self, node: NodeId) -> bool {
+        self.is_node_in(node)
+    }
+
     pub fn is_arc_in(&self, from: NodeId, into: NodeId) -> bool {
         // if the nodes do not exist, then the arc does not exist
         if !self.is_node_in(from) || !self.is_node_in(into) {
@@ -64,27 +194,37 @@ impl<'g, N, A> GraphNetwork<N, A> {
         // if the same arc is in both the outarcs and inarcs, then it is an arc which connects the two nodes
         for arc in &self.arcs_from[from] {
             // skip 'None' arcs
-            if self.arc_data[arc.clone()].is_some() && self.arcs_into[into].contains(&arc) {
+            if self.arc_data[load(*arc)].is_some() && self.arcs_into[into].contains(arc) {
                 return true;
             }
         }
-        return false;
+        false
     }
 
+    /// `None` if the id is out of range or the node has been removed. it never panics.
     pub fn data_of_node(&self, node: NodeId) -> Option<&N> {
-        self.node_data[node].as_ref()
+        self.node_data.get(node)?.as_ref()
     }
 
+    /// `None` if the id is out of range or the node has been removed. it never panics.
     pub fn mut_data_of_node(&mut self, node: NodeId) -> Option<&mut N> {
-        self.node_data[node].as_mut()
+        self.node_data.get_mut(node)?.as_mut()
+    }
+
+    /// `(from, into)` of the arc. `None` if the id is out of range or the arc has been disconnected.
+    pub fn endpoints_of_arc(&self, arc: ArcId) -> Option<(NodeId, NodeId)> {
+        self.data_of_arc(arc)?;
+        Some(self.arc_connections[arc].ends())
     }
 
+    /// `None` if the id is out of range or the arc has been disconnected. it never panics.
     pub fn data_of_arc(&self, arc: ArcId) -> Option<&A> {
-        self.arc_data[arc].as_ref()
+        self.arc_data.get(arc)?.as_ref()
     }
 
+    /// `None` if the id is out of range or the arc has been disconnected. it never panics.
     pub fn mut_data_of_arc(&mut self, arc: ArcId) -> Option<&mut A> {
-        self.arc_data[arc].as_mut()
+        self.arc_data.get_mut(arc)?.as_mut()
     }
 
     pub fn between_nodes(&'g self, from: NodeId, into: NodeId) -> impl Iterator<Item = ArcId> + 'g {
@@ -95,16 +235,29 @@ impl<'g, N, A> GraphNetwork<N, A> {
             }
             // if the same arc is in both the outarcs and inarcs, then it is an arc which connects the two nodes
             for arc_id in &self.arcs_from[from] {
-                if self.arc_data[arc_id.clone()].is_some() {
-                    if self.arcs_into[into].contains(arc_id) {
-                        co.yield_(arc_id.clone()).await;
-                    }
+                if self.arc_data[load(*arc_id)].is_some() && self.arcs_into[into].contains(arc_id) {
+                    co.yield_(load(*arc_id)).await;
                 }
             }
         })
         .into_iter()
     }
 
+    /// the arc from `from` into `into`. with parallel arcs, the first connected one (the smallest id) is returned.
+    /// `None` if there is no such arc, including when either node does not exist.
+    pub fn arc_between(&self, from: NodeId, into: NodeId) -> Option<ArcId> {
+        if !self.is_node_in(from) || !self.is_node_in(into) {
+            return None;
+        }
+        self.from_node(from)
+            .find(|&(target, _)| target == into)
+            .map(|(_, arc_id)| arc_id)
+    }
+
+    /// iterate the nodes which `from` has arcs into, together with the arcs.
+    /// the adjacency is a `Vec` appended on `connect`, so the arcs come in insertion order,
+    /// i.e. ascending `ArcId`, on every run. the same holds for `into_node` and the other adjacency iterators.
+    /// panics if the node does not exist.
     pub fn from_node(&'g self, from: NodeId) -> impl Iterator<Item = (NodeId, ArcId)> + 'g {
         Gen::new(|co| async move {
             // if the nodes do not exist, then the arc does not exist
@@ -112,9 +265,10 @@ impl<'g, N, A> GraphNetwork<N, A> {
                 panic!("Node does not exist");
             }
             // if the same arc is in both the outarcs and inarcs, then it is an arc which connects the two nodes
-            for arc_id in &self.arcs_from[from] {
-                if self.arc_data[arc_id.clone()].is_some() {
-                    co.yield_((self.arc_connections[arc_id.clone()].into, arc_id.clone()))
+            for &arc_id in &self.arcs_from[from] {
+                let arc_id = load(arc_id);
+                if self.arc_data[arc_id].is_some() {
+                    co.yield_((load(self.arc_connections[arc_id].into), arc_id))
                         .await;
                 }
             }
@@ -122,6 +276,9 @@ impl<'g, N, A> GraphNetwork<N, A> {
         .into_iter()
     }
 
+    /// iterate the predecessors of `into` together with the arcs connecting them.
+    /// the reverse adjacency (`arcs_into`) is maintained on `connect`, so this takes O(in-degree) time.
+    /// panics if the node does not exist.
     pub fn into_node(&'g self, into: NodeId) -> impl Iterator<Item = (NodeId, ArcId)> + 'g {
         Gen::new(|co| async move {
             // if the nodes do not exist, then the arc does not exist
@@ -129,9 +286,10 @@ impl<'g, N, A> GraphNetwork<N, A> {
                 panic!("Node does not exist");
             }
             // if the same arc is in both the outarcs and inarcs, then it is an arc which connects the two nodes
-            for arc_id in &self.arcs_into[into] {
-                if self.arc_data[arc_id.clone()].is_some() {
-                    co.yield_((self.arc_connections[arc_id.clone()].from, arc_id.clone()))
+            for &arc_id in &self.arcs_into[into] {
+                let arc_id = load(arc_id);
+                if self.arc_data[arc_id].is_some() {
+                    co.yield_((load(self.arc_connections[arc_id].from), arc_id))
                         .await;
                 }
             }
@@ -139,12 +297,254 @@ impl<'g, N, A> GraphNetwork<N, A> {
         .into_iter()
     }
 
+    /// iterate the nodes which `from` has arcs into, together with the data of the arcs.
+    /// same as `from_node` followed by `data_of_arc`, without the second lookup. panics if the node does not exist.
+    pub fn edges_of_node(&'g self, from: NodeId) -> impl Iterator<Item = (NodeId, &'g A)> + 'g {
+        self.arcs_of_node(from).map(|(into, _, data)| (into, data))
+    }
+
+    /// `(into, arc, data)` of every arc from `from`. a plain iterator over the adjacency rather than a generator,
+    /// for the hot loops (e.g. the relaxation of dijkstra). panics if the node does not exist.
+    pub(crate) fn arcs_of_node(
+        &'g self,
+        from: NodeId,
+    ) -> impl Iterator<Item = (NodeId, ArcId, &'g A)> + 'g {
+        if !self.is_node_in(from) {
+            panic!("Node does not exist");
+        }
+        self.arcs_from[from].iter().filter_map(move |&arc_id| {
+            let arc_id = load(arc_id);
+            let data = self.arc_data[arc_id].as_ref()?;
+            Some((load(self.arc_connections[arc_id].into), arc_id, data))
+        })
+    }
+
+    /// alias of `into_node`, kept for the callers which use this name.
+    pub fn to_node(&'g self, into: NodeId) -> impl Iterator<Item = (NodeId, ArcId)> + 'g {
+        self.into_node(into)
+    }
+
+    /// iterate the nodes which `from` has arcs into. a node appears once per arc (parallel arcs are not merged).
+    pub fn neighbors(&'g self, from: NodeId) -> impl Iterator<Item = NodeId> + 'g {
+        self.from_node(from).map(|(into, _)| into)
+    }
+
+    /// number of arcs leaving the node. panics if the node does not exist.
+    pub fn out_degree(&self, node: NodeId) -> usize {
+        self.from_node(node).count()
+    }
+
+    /// number of arcs entering the node. panics if the node does not exist.
+    pub fn in_degree(&self, node: NodeId) -> usize {
+        self.into_node(node).count()
+    }
+
+    /// iterate the nodes with their data, skipping removed ones
+    pub fn iter_nodes(&'g self) -> impl Iterator<Item = (NodeId, &'g N)> + 'g {
+        self.node_data
+            .iter()
+            .enumerate()
+            .filter_map(|(node_id, data)| Some((node_id, data.as_ref()?)))
+    }
+
+    /// iterate the nodes with mutable access to their data, skipping removed ones.
+    /// e.g. to reset or transform the data of every node at once.
+    pub fn node_data_mut(&mut self) -> impl Iterator<Item = (NodeId, &mut N)> {
+        self.node_data
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(node_id, data)| Some((node_id, data.as_mut()?)))
+    }
+
+    /// iterate the arcs as `(arc, from, into, data)`, skipping disconnected ones
+    pub fn iter_arcs(&'g self) -> impl Iterator<Item = (ArcId, NodeId, NodeId, &'g A)> + 'g {
+        self.arc_data
+            .iter()
+            .zip(self.arc_connections.iter())
+            .enumerate()
+            .filter_map(|(arc_id, (data, connection))| {
+                let (from, into) = connection.ends();
+                Some((arc_id, from, into, data.as_ref()?))
+            })
+    }
+
+    /// iterate the nodes reachable from `start` in breadth-first order, starting with `start` itself.
+    pub fn bfs(&'g self, start: NodeId) -> impl Iterator<Item = NodeId> + 'g {
+        Gen::new(move |co| async move {
+            if !self.is_node_in(start) {
+                panic!("Node does not exist");
+            }
+            let mut visited = vec![false; self.node_data.len()];
+            let mut queue = VecDeque::from([start]);
+            visited[start] = true;
+            while let Some(node_id) = queue.pop_front() {
+                co.yield_(node_id).await;
+                for (into, _) in self.from_node(node_id) {
+                    if !visited[into] {
+                        visited[into] = true;
+                        queue.push_back(into);
+                    }
+                }
+            }
+        })
+        .into_iter()
+    }
+
+    /// iterate the nodes reachable from `start` in depth-first (pre-)order, starting with `start` itself.
+    pub fn dfs(&'g self, start: NodeId) -> impl Iterator<Item = NodeId> + 'g {
+        Gen::new(move |co| async move {
+            if !self.is_node_in(start) {
+                panic!("Node does not exist");
+            }
+            let mut visited = vec![false; self.node_data.len()];
+            let mut stack = vec![start];
+            while let Some(node_id) = stack.pop() {
+                if visited[node_id] {
+                    continue;
+                }
+                visited[node_id] = true;
+                co.yield_(node_id).await;
+                // push in reverse, so that the first arc is explored first
+                let children: Vec<NodeId> = self.from_node(node_id).map(|(into, _)| into).collect();
+                stack.extend(children.into_iter().rev().filter(|&into| !visited[into]));
+            }
+        })
+        .into_iter()
+    }
+
+    pub fn reachable_from(&self, start: NodeId) -> HashSet<NodeId> {
+        self.bfs(start).collect()
+    }
+
+    /// partition the nodes into components, ignoring the direction of arcs.
+    /// components are ordered by their smallest node id.
+    pub fn weakly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        let mut visited = vec![false; self.node_data.len()];
+        let mut components = Vec::new();
+        for start in 0..self.node_data.len() {
+            if visited[start] || !self.is_node_in(start) {
+                continue;
+            }
+            visited[start] = true;
+            let mut component = Vec::new();
+            let mut queue = VecDeque::from([start]);
+            while let Some(node_id) = queue.pop_front() {
+                component.push(node_id);
+                for (neighbor, _) in self.from_node(node_id).chain(self.into_node(node_id)) {
+                    if !visited[neighbor] {
+                        visited[neighbor] = true;
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// partition the nodes into components in which every node can reach every other, by Kosaraju's algorithm.
+    /// the arcs are walked backwards with `into_node` in the second pass, so that no transpose is built.
+    /// components are in topological order: no arc goes from a component into an earlier one.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        // first pass: the nodes in the order of finishing a depth-first search
+        let mut visited = vec![false; self.node_data.len()];
+        let mut finished = Vec::new();
+        for start in 0..self.node_data.len() {
+            if visited[start] || !self.is_node_in(start) {
+                continue;
+            }
+            visited[start] = true;
+            let mut stack = vec![(start, self.neighbors(start).collect::<Vec<_>>())];
+            while let Some((node_id, children)) = stack.last_mut() {
+                if let Some(child) = children.pop() {
+                    if !visited[child] {
+                        visited[child] = true;
+                        stack.push((child, self.neighbors(child).collect()));
+                    }
+                } else {
+                    finished.push(*node_id);
+                    stack.pop();
+                }
+            }
+        }
+
+        // second pass: from the last finished node, the nodes reaching it are in its component
+        let mut assigned = vec![false; self.node_data.len()];
+        let mut components = Vec::new();
+        for &root in finished.iter().rev() {
+            if assigned[root] {
+                continue;
+            }
+            assigned[root] = true;
+            let mut component = Vec::new();
+            let mut stack = vec![root];
+            while let Some(node_id) = stack.pop() {
+                component.push(node_id);
+                for (from, _) in self.into_node(node_id) {
+                    if !assigned[from] {
+                        assigned[from] = true;
+                        stack.push(from);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// true if every node can reach every other (including when there are no nodes)
+    pub fn is_strongly_connected(&self) -> bool {
+        self.strongly_connected_components().len() <= 1
+    }
+
+    /// sort the nodes so that every arc goes forward, by Kahn's algorithm.
+    /// if the graph has a cycle, returns an error with a node on the cycle.
+    pub fn topological_sort(&self) -> std::result::Result<Vec<NodeId>, CycleError> {
+        let mut in_degree = vec![0; self.node_data.len()];
+        let mut queue = VecDeque::new();
+        for (node_id, degree) in in_degree.iter_mut().enumerate() {
+            if self.is_node_in(node_id) {
+                *degree = self.in_degree(node_id);
+                if *degree == 0 {
+                    queue.push_back(node_id);
+                }
+            }
+        }
+
+        let mut sorted = Vec::new();
+        while let Some(node_id) = queue.pop_front() {
+            sorted.push(node_id);
+            for (into, _) in self.from_node(node_id) {
+                in_degree[into] -= 1;
+                if in_degree[into] == 0 {
+                    queue.push_back(into);
+                }
+            }
+        }
+
+        // every node left has a predecessor which is also left.
+        // so walking back along them must reach a node twice, which is on a cycle.
+        if let Some(mut node_id) = in_degree.iter().position(|&degree| degree > 0) {
+            let mut walked = vec![false; self.node_data.len()];
+            while !walked[node_id] {
+                walked[node_id] = true;
+                node_id = self
+                    .to_node(node_id)
+                    .map(|(from, _)| from)
+                    .find(|&from| in_degree[from] > 0)
+                    .unwrap();
+            }
+            return Err(CycleError { node: node_id });
+        }
+        Ok(sorted)
+    }
+
     pub fn add_node(&mut self, data: N) -> NodeId {
         let node_id = self.node_data.len();
         self.node_data.push(Some(data));
         self.arcs_into.push(Vec::new());
         self.arcs_from.push(Vec::new());
-        return node_id;
+        node_id
     }
 
     pub fn add_nodes<I: Iterator<Item = N>>(&mut self, data: I) {
@@ -153,6 +553,8 @@ impl<'g, N, A> GraphNetwork<N, A> {
         }
     }
 
+    /// remove the node and every arc whose source or target is the node.
+    /// the slot of the node is left as `None` (tombstone), so that ids of the other nodes stay valid.
     pub fn remove_node(&mut self, node: NodeId) -> Option<N> {
         // do not pop from the vector, as to keep its index the same
         if !self.is_node_in(node) {
@@ -160,10 +562,10 @@ impl<'g, N, A> GraphNetwork<N, A> {
         }
         // release the arcs
         for arc in self.arcs_into[node].clone() {
-            self.disconnect(arc);
+            self.disconnect(load(arc));
         }
         for arc in self.arcs_from[node].clone() {
-            self.disconnect(arc);
+            self.disconnect(load(arc));
         }
         self.arcs_into[node].clear();
         self.arcs_from[node].clear();
@@ -176,10 +578,10 @@ impl<'g, N, A> GraphNetwork<N, A> {
         }
         let arc_id = self.arc_data.len();
         self.arc_data.push(Some(value));
-        self.arc_connections.push(ArcConnection { from, into });
-        self.arcs_from[from].push(arc_id);
-        self.arcs_into[into].push(arc_id);
-        return arc_id;
+        self.arc_connections.push(ArcConnection::new(from, into));
+        self.arcs_from[from].push(store(arc_id));
+        self.arcs_into[into].push(store(arc_id));
+        arc_id
     }
 
     pub fn bulk_connect<I: Iterator<Item = (NodeId, NodeId, A)>>(&mut self, arcs: I) {
@@ -194,30 +596,323 @@ impl<'g, N, A> GraphNetwork<N, A> {
         if self.arc_data.len() <= arc {
             return None;
         }
-        self.arc_data[arc].take()
+        let value = self.arc_data[arc].take()?;
+        // release the arc from the adjacency of both of its ends
+        let (from, into) = self.arc_connections[arc].ends();
+        let arc = store(arc);
+        self.arcs_from[from].retain(|&arc_id| arc_id != arc);
+        self.arcs_into[into].retain(|&arc_id| arc_id != arc);
+        Some(value)
         // arc_connections is left as it.
     }
+
+    /// append the nodes and arcs of `other`, keeping its topology. returns the id offset:
+    /// node `id` of `other` becomes node `id + offset` of `self`. the arcs of `other` are shifted in the same manner,
+    /// by the number of the arc slots of `self` before the union (`arc_data.len()`).
+    /// removed nodes and disconnected arcs of `other` stay removed, so that the offset applies to every id.
+    pub fn union(&mut self, other: GraphNetwork<N, A>) -> NodeId {
+        let node_offset = self.node_data.len();
+        let arc_offset = self.arc_data.len();
+        let shift_arcs = |arcs: Vec<StoredId>| -> Vec<StoredId> {
+            arcs.into_iter().map(|arc| store(load(arc) + arc_offset)).collect()
+        };
+        self.node_data.extend(other.node_data);
+        self.arcs_into.extend(other.arcs_into.into_iter().map(shift_arcs));
+        self.arcs_from.extend(other.arcs_from.into_iter().map(shift_arcs));
+        self.arc_data.extend(other.arc_data);
+        self.arc_connections.extend(other.arc_connections.iter().map(|connection| {
+            let (from, into) = connection.ends();
+            ArcConnection::new(from + node_offset, into + node_offset)
+        }));
+        node_offset
+    }
+
+    /// disconnect every arc whose both ends are the same node. they never shorten a path.
+    /// returns the number of arcs removed.
+    pub fn remove_self_loops(&mut self) -> usize {
+        let self_loops: Vec<ArcId> = (0..self.arc_data.len())
+            .filter(|&arc| {
+                let (from, into) = self.arc_connections[arc].ends();
+                self.arc_data[arc].is_some() && from == into
+            })
+            .collect();
+        for &arc in self_loops.iter() {
+            self.disconnect(arc);
+        }
+        self_loops.len()
+    }
+
+    /// reverse every arc in place, without the copy `transpose` makes.
+    /// ids of nodes and arcs are kept, but arc `(u -> v)` now connects `(v -> u)`.
+    pub fn reverse_in_place(&mut self) {
+        std::mem::swap(&mut self.arcs_into, &mut self.arcs_from);
+        for connection in self.arc_connections.iter_mut() {
+            std::mem::swap(&mut connection.from, &mut connection.into);
+        }
+    }
+
+    /// relabel node `i` as `perm[i]`, moving its data and rewriting the ends of its arcs.
+    /// arc ids are kept, and removed nodes move as tombstones.
+    /// panics if `perm` is not a permutation of `0..n`, `n` being the number of the slots (including removed nodes).
+    pub fn permute_nodes(&mut self, perm: &[NodeId]) {
+        let number_of_nodes = self.node_data.len();
+        assert_eq!(perm.len(), number_of_nodes, "permutation of a wrong length");
+        let mut seen = vec![false; number_of_nodes];
+        for &new_id in perm {
+            assert!(new_id < number_of_nodes && !seen[new_id], "not a permutation");
+            seen[new_id] = true;
+        }
+
+        let mut node_data: Vec<Option<N>> =
+            std::iter::repeat_with(|| None).take(number_of_nodes).collect();
+        let mut arcs_into = vec![Vec::new(); number_of_nodes];
+        let mut arcs_from = vec![Vec::new(); number_of_nodes];
+        for (old_id, &new_id) in perm.iter().enumerate() {
+            std::mem::swap(&mut node_data[new_id], &mut self.node_data[old_id]);
+            std::mem::swap(&mut arcs_into[new_id], &mut self.arcs_into[old_id]);
+            std::mem::swap(&mut arcs_from[new_id], &mut self.arcs_from[old_id]);
+        }
+        self.node_data = node_data;
+        self.arcs_into = arcs_into;
+        self.arcs_from = arcs_from;
+        for connection in self.arc_connections.iter_mut() {
+            let (from, into) = connection.ends();
+            *connection = ArcConnection::new(perm[from], perm[into]);
+        }
+    }
+
+    /// check the internal consistency, and describe the first inconsistency found:
+    /// every live arc connects live nodes and is listed exactly once in the adjacency of both of its ends,
+    /// and the adjacency lists hold only live arcs of the node. O(V + E · degree).
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        let number_of_nodes = self.node_data.len();
+        if self.arcs_into.len() != number_of_nodes || self.arcs_from.len() != number_of_nodes {
+            return Err(format!(
+                "{} nodes but adjacency of {} (into) and {} (from) nodes",
+                number_of_nodes,
+                self.arcs_into.len(),
+                self.arcs_from.len()
+            ));
+        }
+        if self.arc_connections.len() != self.arc_data.len() {
+            return Err(format!(
+                "{} arcs but {} arc connections",
+                self.arc_data.len(),
+                self.arc_connections.len()
+            ));
+        }
+
+        for (arc, connection) in self.arc_connections.iter().enumerate() {
+            if self.arc_data[arc].is_none() {
+                continue;
+            }
+            let (from, into) = connection.ends();
+            if !self.is_node_in(from) || !self.is_node_in(into) {
+                return Err(format!("arc {} ({} -> {}) connects a removed node", arc, from, into));
+            }
+            let times_from = self.arcs_from[from].iter().filter(|&&id| load(id) == arc).count();
+            let times_into = self.arcs_into[into].iter().filter(|&&id| load(id) == arc).count();
+            if times_from != 1 || times_into != 1 {
+                return Err(format!(
+                    "arc {} ({} -> {}) is listed {} times from {} and {} times into {}",
+                    arc, from, into, times_from, from, times_into, into
+                ));
+            }
+        }
+
+        for node in 0..number_of_nodes {
+            let lists = [("from", &self.arcs_from[node]), ("into", &self.arcs_into[node])];
+            for (direction, arcs) in lists {
+                for arc in arcs.iter().map(|&id| load(id)) {
+                    if self.arc_data.get(arc).is_none_or(Option::is_none) {
+                        return Err(format!("dangling arc {} {} node {}", arc, direction, node));
+                    }
+                    let (from, into) = self.arc_connections[arc].ends();
+                    let end = if direction == "from" { from } else { into };
+                    if end != node {
+                        return Err(format!(
+                            "arc {} ({} -> {}) is listed {} node {}",
+                            arc, from, into, direction, node
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
-impl<N: Display,A: Display> Display for GraphNetwork<N, A> {
-    fn fmt(&self, f: &mut Formatter) -> Result {
-        let mut printed = Vec::<NodeId>::new();
+impl<N: Clone, A: Clone> GraphNetwork<N, A> {
+    /// returns the network whose arcs are all reversed.
+    /// ids of nodes and arcs are kept, so that arc `(u -> v)` of `self` is arc `(v -> u)` of the transpose.
+    pub fn transpose(&self) -> Self {
+        GraphNetwork {
+            node_data: self.node_data.clone(),
+            arcs_into: self.arcs_from.clone(),
+            arcs_from: self.arcs_into.clone(),
+            arc_data: self.arc_data.clone(),
+            arc_connections: self
+                .arc_connections
+                .iter()
+                .map(|&ArcConnection { from, into }| ArcConnection {
+                    from: into,
+                    into: from,
+                })
+                .collect(),
+        }
+    }
+
+    /// returns the subgraph induced by `nodes`, and the original id of each node of it.
+    /// only the arcs whose both ends are in `nodes` are kept. node ids are remapped to `0..k` in ascending order
+    /// of the original ids, so that `mapping[new_id]` is the original id. ids not in the network are ignored.
+    pub fn subgraph(&self, nodes: &HashSet<NodeId>) -> (Self, Vec<NodeId>) {
+        let mut mapping: Vec<NodeId> = nodes
+            .iter()
+            .copied()
+            .filter(|&node| self.is_node_in(node))
+            .collect();
+        mapping.sort();
+        let new_id_of: HashMap<NodeId, NodeId> = mapping
+            .iter()
+            .enumerate()
+            .map(|(new_id, &old_id)| (old_id, new_id))
+            .collect();
+
+        let mut subgraph = GraphNetwork::new();
+        subgraph.add_nodes(
+            mapping
+                .iter()
+                .map(|&old_id| self.data_of_node(old_id).unwrap().clone()),
+        );
+        for &old_from in mapping.iter() {
+            for (old_into, arc_id) in self.from_node(old_from) {
+                if let Some(&new_into) = new_id_of.get(&old_into) {
+                    let data = self.data_of_arc(arc_id).unwrap().clone();
+                    subgraph.connect(new_id_of[&old_from], new_into, data);
+                }
+            }
+        }
+        (subgraph, mapping)
+    }
+}
+
+impl<N: Default, A: Clone> GraphNetwork<N, A> {
+    /// copy of the topology and the arc data, with `N::default()` for every node instead of a clone of its data.
+    /// e.g. a fresh network for another query, without the state of the last one. ids (and removals) are kept.
+    pub fn clone_with_default_data(&self) -> Self {
+        GraphNetwork {
+            node_data: self
+                .node_data
+                .iter()
+                .map(|node| node.as_ref().map(|_| N::default()))
+                .collect(),
+            arcs_into: self.arcs_into.clone(),
+            arcs_from: self.arcs_from.clone(),
+            arc_data: self.arc_data.clone(),
+            arc_connections: self.arc_connections.clone(),
+        }
+    }
+}
+
+impl<N, A: Clone> GraphNetwork<N, A> {
+    /// returns the network whose node data are mapped by `f`, given the id of each node.
+    /// arcs and the topology (including ids) are kept. removed nodes stay removed.
+    pub fn map_nodes<M>(&self, f: impl Fn(NodeId, &N) -> M) -> GraphNetwork<M, A> {
+        GraphNetwork {
+            node_data: self
+                .node_data
+                .iter()
+                .enumerate()
+                .map(|(node_id, node)| node.as_ref().map(|node| f(node_id, node)))
+                .collect(),
+            arcs_into: self.arcs_into.clone(),
+            arcs_from: self.arcs_from.clone(),
+            arc_data: self.arc_data.clone(),
+            arc_connections: self.arc_connections.clone(),
+        }
+    }
+
+    /// `(from, into, data)` of every arc in the order of `ArcId`, the input of `network_factory` (and `collect`).
+    /// disconnected arcs, including the ones of removed nodes, are skipped. node ids are not remapped,
+    /// so building again from the list restores removed nodes as isolated ones (call `clean` first to avoid it).
+    pub fn to_edge_list(&self) -> Vec<(NodeId, NodeId, A)> {
+        self.iter_arcs()
+            .map(|(_, from, into, data)| (from, into, data.clone()))
+            .collect()
+    }
+}
+
+impl<N: Clone, A> GraphNetwork<N, A> {
+    /// returns the network whose arc data are mapped by `f`. nodes and the topology (including ids) are kept.
+    /// disconnected arcs stay disconnected.
+    pub fn map_arcs<B>(&self, f: impl Fn(&A) -> B) -> GraphNetwork<N, B> {
+        GraphNetwork {
+            node_data: self.node_data.clone(),
+            arcs_into: self.arcs_into.clone(),
+            arcs_from: self.arcs_from.clone(),
+            arc_data: self.arc_data.iter().map(|arc| arc.as_ref().map(&f)).collect(),
+            arc_connections: self.arc_connections.clone(),
+        }
+    }
+}
+
+/// collect `(from, into, data)` arcs into a network, in the same manner as `network_factory`:
+/// nodes `0..=max endpoint` are added with `N::default()`, and arcs get ids in the order of the iterator.
+impl<N: Default, A> FromIterator<(NodeId, NodeId, A)> for GraphNetwork<N, A> {
+    fn from_iter<I: IntoIterator<Item = (NodeId, NodeId, A)>>(iter: I) -> Self {
+        let arcs: Vec<(NodeId, NodeId, A)> = iter.into_iter().collect();
+        let number_of_nodes = arcs
+            .iter()
+            .map(|&(from, into, _)| from.max(into) + 1)
+            .max()
+            .unwrap_or(0);
+        let mut network = GraphNetwork::with_capacity(number_of_nodes, arcs.len());
+        network.add_nodes(std::iter::repeat_with(N::default).take(number_of_nodes));
+        network.bulk_connect(arcs.into_iter());
+        network
+    }
+}
+
+impl<N: Display, A: Display> GraphNetwork<N, A> {
+    /// export the network as a GraphViz `digraph`.
+    /// nodes are labeled with their id and data, and arcs with their data. removed nodes are skipped.
+    pub fn to_dot(&self) -> String {
+        fn escape(label: String) -> String {
+            label.replace('\\', "\\\\").replace('"', "\\\"")
+        }
+        let mut dot = String::from("digraph {\n");
+        for (node_id, node_data) in self.node_data.iter().enumerate() {
+            if let Some(node_data) = node_data {
+                let label = escape(format!("{}({})", node_id, node_data));
+                dot.push_str(&format!("    {} [label=\"{}\"];\n", node_id, label));
+            }
+        }
         for (node_id, node_data) in self.node_data.iter().enumerate() {
-            if node_data.is_none() {continue;}
-            let node_data = node_data.as_ref().unwrap();
+            if node_data.is_none() {
+                continue;
+            }
             for (into, arc_id) in self.from_node(node_id) {
-                if printed.contains(&into) {continue;}
-                let arc_data = self.arc_data[arc_id].as_ref().unwrap();
-                let into_data = self.node_data[into].as_ref().unwrap();
-                writeln!(f, "{}({}) -- {} -> {}({})", node_id, node_data, arc_data, into, into_data)?;
+                let label = escape(self.arc_data[arc_id].as_ref().unwrap().to_string());
+                dot.push_str(&format!("    {} -> {} [label=\"{}\"];\n", node_id, into, label));
             }
-            for (from, arc_id) in self.into_node(node_id) {
-                if printed.contains(&from) {continue;}
-                let arc_data = self.arc_data[arc_id].as_ref().unwrap();
-                let from_data = self.node_data[from].as_ref().unwrap();
-                writeln!(f, "{}({}) <- {} -- {}({})", node_id,node_data, arc_data, from,from_data)?;
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// every node is printed as `id(data)`, followed by its outgoing arcs as `from -> into [data]`.
+/// removed nodes are skipped.
+impl<N: Display, A: Display> Display for GraphNetwork<N, A> {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        for (node_id, node_data) in self.node_data.iter().enumerate() {
+            if let Some(node_data) = node_data {
+                writeln!(f, "{}({})", node_id, node_data)?;
+                for (into, arc_id) in self.from_node(node_id) {
+                    let arc_data = self.arc_data[arc_id].as_ref().unwrap();
+                    writeln!(f, "  {} -> {} [{}]", node_id, into, arc_data)?;
+                }
             }
-            printed.push(node_id);
         }
         Ok(())
     }
@@ -246,17 +941,449 @@ mod tests {
         );
         network.disconnect(3);
         network.remove_node(6);
+        assert_eq!(network.validate(), Ok(()));
         network = network.clean();
+        assert_eq!(network.validate(), Ok(()));
         println!("Network: {:?}", network);
         assert_eq!(network.from_node(3).collect::<Vec<_>>(), vec![(5, 5)]);
         assert_eq!(
             network.into_node(3).collect::<Vec<_>>(),
             vec![(1, 2), (2, 3)]
         );
-        assert_eq!(network.is_arc_in(1, 4), false);
-        assert_eq!(network.is_node_in(1), true);
-        assert_eq!(network.is_node_in(6), false);
+        assert!(!network.is_arc_in(1, 4));
+        assert!(network.is_node_in(1));
+        assert!(!network.is_node_in(6));
         assert_eq!(network.between_nodes(0, 1).collect::<Vec<_>>(), vec![0]);
         assert_eq!(network.data_of_node(0), Some(&0));
     }
+
+    #[test]
+    fn test_node_exists() {
+        let mut network = mini_instance();
+        network.remove_node(2);
+        // present
+        assert!(network.node_exists(1));
+        assert_eq!(network.data_of_node(1), Some(&1));
+        // tombstoned
+        assert!(!network.node_exists(2));
+        assert_eq!(network.data_of_node(2), None);
+        assert_eq!(network.mut_data_of_node(2), None);
+        // out of range
+        assert!(!network.node_exists(6));
+        assert_eq!(network.data_of_node(6), None);
+        assert_eq!(network.mut_data_of_node(100), None);
+    }
+
+    #[test]
+    fn test_iter_nodes_and_arcs() {
+        let mut network = mini_instance();
+        assert_eq!(network.iter_nodes().count(), network.node_count());
+        assert_eq!(network.iter_arcs().count(), network.arc_count());
+        assert_eq!(network.node_count(), 6);
+        assert_eq!(network.arc_count(), 8);
+        assert_eq!(network.iter_arcs().nth(3), Some((3, 1, 2, &1)));
+
+        network.disconnect(0);
+        network.remove_node(5);
+        assert_eq!(network.iter_nodes().count(), network.node_count());
+        assert_eq!(network.iter_arcs().count(), network.arc_count());
+        assert_eq!(
+            network.iter_nodes().map(|(id, _)| id).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4]
+        );
+        assert!(network.iter_arcs().all(|(arc_id, _, _, _)| arc_id != 0));
+    }
+
+    #[test]
+    fn test_node_data_mut() {
+        let mut network = mini_instance();
+        network.remove_node(2);
+        for (node_id, data) in network.node_data_mut() {
+            *data = node_id * 10;
+        }
+        assert_eq!(network.node_data_mut().count(), 5);
+        assert_eq!(
+            network.iter_nodes().map(|(_, &data)| data).collect::<Vec<_>>(),
+            vec![0, 10, 30, 40, 50]
+        );
+        assert_eq!(network.data_of_node(2), None);
+    }
+
+    #[test]
+    fn test_arc_between() {
+        let mut network = mini_instance();
+        let arc = network.arc_between(0, 1).unwrap();
+        assert_eq!(arc, 0);
+        assert_eq!(network.data_of_arc(arc), Some(&1));
+        assert_eq!(network.arc_between(1, 0), None);
+        assert_eq!(network.arc_between(0, 100), None);
+
+        // the first one among parallel arcs
+        let parallel = network.connect(0, 1, 5);
+        assert_eq!(network.arc_between(0, 1), Some(arc));
+        network.disconnect(arc);
+        assert_eq!(network.arc_between(0, 1), Some(parallel));
+    }
+
+    #[test]
+    fn test_edges_of_node() {
+        let mut network = mini_instance();
+        network.disconnect(1);
+        for node_id in 0..6 {
+            let expected: Vec<(NodeId, &usize)> = network
+                .from_node(node_id)
+                .map(|(into, arc_id)| (into, network.data_of_arc(arc_id).unwrap()))
+                .collect();
+            assert_eq!(network.edges_of_node(node_id).collect::<Vec<_>>(), expected);
+        }
+        assert_eq!(network.edges_of_node(0).collect::<Vec<_>>(), vec![(1, &1), (3, &2)]);
+    }
+
+    #[test]
+    fn test_degree() {
+        let mut network = mini_instance();
+        assert_eq!(
+            (0..6).map(|node| network.out_degree(node)).collect::<Vec<_>>(),
+            vec![3, 1, 0, 1, 2, 1]
+        );
+        assert_eq!(
+            (0..6).map(|node| network.in_degree(node)).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 1, 1]
+        );
+        network.disconnect(2);
+        assert_eq!(network.out_degree(0), 2);
+        assert_eq!(network.in_degree(3), 2);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let network = mini_instance();
+        assert_eq!(network.neighbors(0).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(network.neighbors(2).count(), 0);
+        assert!(network.has_edge(4, 5));
+        assert!(!network.has_edge(5, 4));
+        assert!(!network.has_edge(0, 10));
+    }
+
+    fn mini_instance() -> GraphNetwork<usize, usize> {
+        let mut network = GraphNetwork::<usize, usize>::new();
+        network.add_nodes(vec![0, 1, 2, 3, 4, 5].into_iter());
+        network.bulk_connect(
+            vec![
+                (0, 1, 1),
+                (0, 2, 3),
+                (0, 3, 2),
+                (1, 2, 1),
+                (3, 4, 2),
+                (4, 3, 2),
+                (4, 5, 2),
+                (5, 3, 2),
+            ]
+            .into_iter(),
+        );
+        network
+    }
+
+    #[test]
+    fn test_transpose() {
+        let network = mini_instance();
+        let transposed = network.transpose();
+        for node_id in 0..6 {
+            assert_eq!(
+                transposed.from_node(node_id).collect::<Vec<_>>(),
+                network.to_node(node_id).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                transposed.to_node(node_id).collect::<Vec<_>>(),
+                network.from_node(node_id).collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(transposed.data_of_arc(1), Some(&3));
+    }
+
+    #[test]
+    fn test_reverse_in_place() {
+        let network = mini_instance();
+        let mut reversed = mini_instance();
+        reversed.reverse_in_place();
+        for node_id in 0..6 {
+            assert_eq!(
+                reversed.from_node(node_id).collect::<Vec<_>>(),
+                network.to_node(node_id).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                reversed.to_node(node_id).collect::<Vec<_>>(),
+                network.from_node(node_id).collect::<Vec<_>>()
+            );
+        }
+        // arc 1 is still the arc of weight 3, from 2 into 0 now
+        assert_eq!(reversed.endpoints_of_arc(1), Some((2, 0)));
+        assert_eq!(reversed.data_of_arc(1), Some(&3));
+
+        reversed.reverse_in_place();
+        for arc_id in 0..8 {
+            assert_eq!(reversed.endpoints_of_arc(arc_id), network.endpoints_of_arc(arc_id));
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut network = mini_instance();
+        assert_eq!(network.validate(), Ok(()));
+        network.disconnect(3);
+        network.remove_node(4);
+        network.connect(5, 0, 1);
+        network.reverse_in_place();
+        assert_eq!(network.validate(), Ok(()));
+        assert_eq!(network.clone().clean().validate(), Ok(()));
+        assert_eq!(GraphNetwork::<(), ()>::new().validate(), Ok(()));
+
+        // listed twice
+        let mut corrupted = network.clone();
+        corrupted.arcs_from[1].push(store(0));
+        assert!(corrupted.validate().unwrap_err().contains("arc 0 "));
+
+        // not listed at all
+        let mut corrupted = network.clone();
+        corrupted.arcs_into[0].clear();
+        assert!(corrupted.validate().is_err());
+
+        // a disconnected arc left in the adjacency
+        let mut corrupted = network.clone();
+        corrupted.arc_data[8] = None;
+        assert!(corrupted.validate().unwrap_err().starts_with("dangling arc 8"));
+
+        // a removed node with live arcs
+        let mut corrupted = network.clone();
+        corrupted.node_data[0] = None;
+        assert!(corrupted.validate().unwrap_err().contains("removed node"));
+
+        // adjacency out of sync with the nodes
+        let mut corrupted = network.clone();
+        corrupted.arcs_into.pop();
+        assert!(corrupted.validate().is_err());
+    }
+
+    #[test]
+    fn test_map_nodes() {
+        let mut network = mini_instance();
+        network.remove_node(2);
+        let mapped: GraphNetwork<String, usize> =
+            network.map_nodes(|node_id, _| format!("node {}", node_id));
+        assert_eq!(mapped.data_of_node(4), Some(&"node 4".to_string()));
+        assert_eq!(mapped.data_of_node(2), None);
+        assert_eq!(mapped.node_count(), 5);
+        for node_id in [0, 1, 3, 4, 5] {
+            assert_eq!(
+                mapped.from_node(node_id).collect::<Vec<_>>(),
+                network.from_node(node_id).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                mapped.to_node(node_id).collect::<Vec<_>>(),
+                network.to_node(node_id).collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(mapped.data_of_arc(4), Some(&2));
+        assert_eq!(mapped.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_deterministic_arc_order() {
+        let first = mini_instance();
+        let second = mini_instance();
+        for node_id in 0..6 {
+            let arcs: Vec<(NodeId, ArcId)> = first.from_node(node_id).collect();
+            assert_eq!(arcs, second.from_node(node_id).collect::<Vec<_>>());
+            assert!(arcs.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        }
+        assert_eq!(first.from_node(0).collect::<Vec<_>>(), vec![(1, 0), (2, 1), (3, 2)]);
+
+        // disconnection and later arcs keep the order
+        let mut network = mini_instance();
+        network.connect(0, 4, 1);
+        network.disconnect(1);
+        network.connect(0, 2, 5);
+        assert_eq!(network.from_node(0).collect::<Vec<_>>(), vec![(1, 0), (3, 2), (4, 8), (2, 9)]);
+        assert_eq!(network.into_node(3).collect::<Vec<_>>(), vec![(0, 2), (4, 5), (5, 7)]);
+    }
+
+    #[test]
+    fn test_density() {
+        let network = mini_instance();
+        assert_eq!(network.density(), 8.0 / 30.0);
+        assert!(network.is_dense());
+        assert_eq!(GraphNetwork::<usize, usize>::new().density(), 0.0);
+
+        // the instances of the benchmark
+        let sparse: GraphNetwork<usize, usize> = (0..1000)
+            .flat_map(|node_id| [3, 7, 13].map(|step| (node_id, (node_id + step) % 1000, 1)))
+            .collect();
+        assert!(sparse.density() < 0.01);
+        assert!(!sparse.is_dense());
+        let dense: GraphNetwork<usize, usize> = (0..1000)
+            .flat_map(|from| (from..1000).map(move |into| (from, into, 1)))
+            .collect();
+        assert!(dense.density() > 0.5);
+        assert!(dense.is_dense());
+    }
+
+    #[test]
+    fn test_degree_statistics() {
+        let mut network = mini_instance();
+        assert_eq!(network.average_degree(), 8.0 / 6.0);
+        let histogram = network.degree_histogram();
+        assert_eq!(histogram, HashMap::from([(0, 1), (1, 3), (2, 1), (3, 1)]));
+        assert_eq!(histogram.values().sum::<usize>(), network.node_count());
+
+        // 0 loses an arc into 2, and 1 its only one
+        network.remove_node(2);
+        assert_eq!(network.average_degree(), 6.0 / 5.0);
+        assert_eq!(network.degree_histogram(), HashMap::from([(0, 1), (1, 2), (2, 2)]));
+        assert_eq!(GraphNetwork::<usize, usize>::new().average_degree(), 0.0);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut network = mini_instance();
+        network.remove_node(2);
+        let dot = network.to_dot();
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("    0 [label=\"0(0)\"];\n"));
+        assert!(dot.contains("    0 -> 1 [label=\"1\"];\n"));
+        assert!(dot.contains("    4 -> 5 [label=\"2\"];\n"));
+        assert!(!dot.contains("    2 ["));
+        assert!(!dot.contains("-> 2 "));
+    }
+
+    #[test]
+    fn test_display() {
+        let mut network = mini_instance();
+        network.remove_node(2);
+        let expected = [
+            "0(0)",
+            "  0 -> 1 [1]",
+            "  0 -> 3 [2]",
+            "1(1)",
+            "3(3)",
+            "  3 -> 4 [2]",
+            "4(4)",
+            "  4 -> 3 [2]",
+            "  4 -> 5 [2]",
+            "5(5)",
+            "  5 -> 3 [2]",
+        ];
+        assert_eq!(network.to_string(), expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn test_traversal() {
+        let mut network = mini_instance();
+        let entrance = network.add_node(6);
+        network.connect(entrance, 0, 1);
+
+        let bfs: Vec<NodeId> = network.bfs(0).collect();
+        assert_eq!(bfs, vec![0, 1, 2, 3, 4, 5]);
+        // direct neighbors of the start come before their neighbors
+        let position = |node_id: NodeId| bfs.iter().position(|&id| id == node_id).unwrap();
+        for (neighbor, _) in network.from_node(0) {
+            assert!(position(neighbor) < position(4));
+            assert!(position(neighbor) < position(5));
+        }
+
+        assert_eq!(network.dfs(0).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(network.dfs(3).collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!(network.bfs(2).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(network.bfs(entrance).count(), 7);
+    }
+
+    #[test]
+    fn test_from_iterator() {
+        let network: GraphNetwork<(), usize> =
+            vec![(0, 1, 1), (0, 2, 3), (2, 3, 2), (4, 3, 1)].into_iter().collect();
+        assert_eq!(network.node_count(), 5);
+        assert_eq!(network.arc_count(), 4);
+        assert_eq!(network.bfs(0).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+        let empty: GraphNetwork<(), usize> = std::iter::empty().collect();
+        assert_eq!(empty.node_count(), 0);
+    }
+
+    #[test]
+    fn test_connectivity() {
+        let mut network = mini_instance();
+        network.add_nodes(vec![6, 7, 8].into_iter());
+        network.bulk_connect(vec![(7, 6, 1), (8, 6, 1)].into_iter());
+
+        assert_eq!(network.reachable_from(3), HashSet::from([3, 4, 5]));
+        assert_eq!(network.reachable_from(6), HashSet::from([6]));
+
+        let mut components = network.weakly_connected_components();
+        for component in components.iter_mut() {
+            component.sort();
+        }
+        assert_eq!(components, vec![vec![0, 1, 2, 3, 4, 5], vec![6, 7, 8]]);
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let mut network = GraphNetwork::<(), usize>::new();
+        network.add_nodes(std::iter::repeat_n((), 5));
+        // {0, 1, 2} and {3, 4}, linked by 2 -> 3
+        network.bulk_connect(
+            vec![(0, 1, 1), (1, 2, 1), (2, 0, 1), (2, 3, 1), (3, 4, 1), (4, 3, 1)].into_iter(),
+        );
+        let mut components = network.strongly_connected_components();
+        for component in components.iter_mut() {
+            component.sort();
+        }
+        assert_eq!(components, vec![vec![0, 1, 2], vec![3, 4]]);
+        assert!(!network.is_strongly_connected());
+
+        network.connect(4, 0, 1);
+        assert_eq!(network.strongly_connected_components().len(), 1);
+        assert!(network.is_strongly_connected());
+
+        // every node but the cycle of 3, 4 and 5 is a component by itself
+        assert_eq!(mini_instance().strongly_connected_components().len(), 4);
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let mut network = mini_instance();
+        // 3 -> 4 -> 3 and 3 -> 4 -> 5 -> 3 are the cycles
+        let error = network.topological_sort().unwrap_err();
+        assert!([3, 4, 5].contains(&error.node));
+
+        network.remove_node(4);
+        network.add_nodes(vec![6].into_iter());
+        network.bulk_connect(vec![(6, 0, 1), (2, 3, 1)].into_iter());
+        let sorted = network.topological_sort().unwrap();
+        assert_eq!(sorted.len(), 6);
+        let position = |node_id: NodeId| sorted.iter().position(|&id| id == node_id).unwrap();
+        for from in sorted.iter().copied() {
+            for (into, _) in network.from_node(from) {
+                assert!(position(from) < position(into));
+            }
+        }
+
+        network.connect(5, 5, 1);
+        assert_eq!(network.topological_sort(), Err(CycleError { node: 5 }));
+    }
+
+    #[test]
+    fn test_to_node() {
+        let mut network = GraphNetwork::<usize, i32>::new();
+        network.add_nodes(vec![0, 1, 2, 3, 4].into_iter());
+        network.bulk_connect(
+            vec![(0, 1, 1), (0, 2, 1), (1, 2, 1), (2, 0, 1), (3, 4, 1), (4, 4, 1)].into_iter(),
+        );
+        for from in 0..5 {
+            for (into, arc_id) in network.from_node(from) {
+                assert!(network.to_node(into).any(|incoming| incoming == (from, arc_id)));
+            }
+        }
+        assert_eq!(network.to_node(2).collect::<Vec<_>>(), vec![(0, 1), (1, 2)]);
+        assert_eq!(network.to_node(3).count(), 0);
+    }
 }
@@ -0,0 +1,121 @@
+use std::io::{self, BufRead};
+
+use crate::dijkstra::*;
+use crate::graph::*;
+
+fn malformed(line_number: usize, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("line {}: {}", line_number, message),
+    )
+}
+
+fn parse_field(field: Option<&str>, line_number: usize, name: &str) -> io::Result<usize> {
+    field
+        .ok_or_else(|| malformed(line_number, &format!("missing {}", name)))?
+        .parse()
+        .map_err(|_| malformed(line_number, &format!("invalid {}", name)))
+}
+
+/// read a network from the DIMACS shortest path format (`.gr`).
+/// - `c ...` lines are comments
+/// - `p sp <nodes> <arcs>` is the header, which must come before any arc
+/// - `a <from> <into> <weight>` is an arc. nodes are 1-based in the file and 0-based in the network.
+pub fn network_from_dimacs(
+    reader: impl BufRead,
+) -> io::Result<GraphNetwork<DijkstraNode, DijkstraArc>> {
+    let mut network = None;
+    let mut number_of_nodes = 0;
+    let mut expected_number_of_arcs = 0;
+    let mut number_of_arcs = 0;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = index + 1;
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            None | Some("c") => continue,
+            Some("p") => {
+                if network.is_some() {
+                    return Err(malformed(line_number, "duplicated problem line"));
+                }
+                if fields.next() != Some("sp") {
+                    return Err(malformed(line_number, "problem type must be `sp`"));
+                }
+                number_of_nodes = parse_field(fields.next(), line_number, "number of nodes")?;
+                expected_number_of_arcs =
+                    parse_field(fields.next(), line_number, "number of arcs")?;
+                network = Some(empty_network(number_of_nodes, expected_number_of_arcs));
+            }
+            Some("a") => {
+                let network = network
+                    .as_mut()
+                    .ok_or_else(|| malformed(line_number, "arc before problem line"))?;
+                let from = parse_field(fields.next(), line_number, "source node")?;
+                let into = parse_field(fields.next(), line_number, "target node")?;
+                let weight = parse_field(fields.next(), line_number, "weight")?;
+                for node in [from, into] {
+                    if node == 0 || node > number_of_nodes {
+                        return Err(malformed(line_number, "node out of range"));
+                    }
+                }
+                network.connect(from - 1, into - 1, DijkstraArc::new(weight));
+                number_of_arcs += 1;
+            }
+            Some(_) => return Err(malformed(line_number, "unknown line type")),
+        }
+        if fields.next().is_some() {
+            return Err(malformed(line_number, "too many fields"));
+        }
+    }
+
+    let network = network.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing problem line")
+    })?;
+    if number_of_arcs != expected_number_of_arcs {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "expected {} arcs but found {}",
+                expected_number_of_arcs, number_of_arcs
+            ),
+        ));
+    }
+    Ok(network)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_network_from_dimacs() {
+        let input = "c sample graph\n\
+                     p sp 4 4\n\
+                     c arcs\n\
+                     a 1 2 3\n\
+                     a 1 3 1\n\
+                     a 3 2 1\n\
+                     \n\
+                     a 2 4 2\n";
+        let mut network = network_from_dimacs(input.as_bytes()).unwrap();
+        assert!(network.is_arc_in(0, 1));
+        assert!(network.is_arc_in(1, 3));
+        assert!(!network.is_arc_in(3, 1));
+        dijkstra(&mut network, 0).unwrap();
+        assert_eq!(network.distances(), vec![0, 2, 1, 4]);
+
+        let errors = [
+            "a 1 2 3\n",
+            "p sp 2 1\na 1 3 1\n",
+            "p sp 2 1\na 1 2\n",
+            "p sp 2 1\na 1 2 x\n",
+            "p sp 2 1\nx 1 2 1\n",
+            "p sp 2 2\na 1 2 1\n",
+        ];
+        for input in errors {
+            let error = network_from_dimacs(input.as_bytes()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use crate::bellman_ford::*;
+use crate::dijkstra::*;
+use crate::graph::*;
+
+/// potential of every node: the distance from a virtual node joined to all the nodes by zero-weight arcs.
+/// `h[into] <= h[from] + weight` holds for every arc, so `weight + h[from] - h[into]` is never negative.
+/// removed nodes get 0.
+fn potentials(
+    network: &GraphNetwork<DijkstraNode<i64>, DijkstraArc<i64>>,
+) -> std::result::Result<Vec<i64>, NegativeCycle> {
+    let number_of_nodes = network.node_data.len();
+    let mut augmented = network.clone();
+    let virtual_node = augmented.add_node(DijkstraNode::default());
+    for node_id in 0..number_of_nodes {
+        if augmented.is_node_in(node_id) {
+            augmented.connect(virtual_node, node_id, DijkstraArc::new(0));
+        }
+    }
+    // no arc goes into the virtual node, so a negative cycle found is made of the original nodes
+    bellman_ford(&mut augmented, virtual_node).map_err(|error| match error {
+        BellmanFordError::NegativeCycle(error) => error,
+        BellmanFordError::InvalidStart(_) => unreachable!("the virtual node has just been added"),
+    })?;
+    Ok((0..number_of_nodes)
+        .map(|node_id| augmented.data_of_node(node_id).map_or(0, |node| node.distance))
+        .collect())
+}
+
+/// all pairs shortest paths which allows negative weights. O(V E + V · (E + V log V)).
+/// the arcs are reweighted by `bellman_ford` once so that none is negative, then `dijkstra` runs from every node.
+/// `matrix[from][into]` is the distance in the original weights, `None` if unreachable.
+/// rows of removed nodes are all `None` as in `all_pairs_shortest_paths`.
+/// returns an error if the network has a negative cycle anywhere, even one unreachable from some nodes.
+pub fn johnson(
+    network: &GraphNetwork<DijkstraNode<i64>, DijkstraArc<i64>>,
+) -> std::result::Result<Vec<Vec<Option<i64>>>, NegativeCycle> {
+    let potentials = potentials(network)?;
+
+    let mut reweighted = network.clone();
+    for arc_id in 0..reweighted.arc_data.len() {
+        if let Some((from, into)) = reweighted.endpoints_of_arc(arc_id) {
+            let arc = reweighted.mut_data_of_arc(arc_id).unwrap();
+            arc.weight += potentials[from] - potentials[into];
+            debug_assert!(arc.weight >= 0);
+        }
+    }
+
+    // every path from `from` to `into` weighs `potentials[from] - potentials[into]` more after reweighting
+    let matrix = all_pairs_shortest_paths(&reweighted)
+        .into_iter()
+        .enumerate()
+        .map(|(from, row)| {
+            row.into_iter()
+                .enumerate()
+                .map(|(into, distance)| {
+                    distance.map(|distance| distance - potentials[from] + potentials[into])
+                })
+                .collect()
+        })
+        .collect();
+    Ok(matrix)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// O(V³) reference over the arcs
+    fn floyd_warshall_reference(
+        arcs: &[(NodeId, NodeId, i64)],
+        number_of_nodes: usize,
+    ) -> Vec<Vec<Option<i64>>> {
+        let mut matrix = vec![vec![None; number_of_nodes]; number_of_nodes];
+        for (node_id, row) in matrix.iter_mut().enumerate() {
+            row[node_id] = Some(0);
+        }
+        for &(from, into, weight) in arcs {
+            if matrix[from][into].is_none_or(|distance| weight < distance) {
+                matrix[from][into] = Some(weight);
+            }
+        }
+        for via in 0..number_of_nodes {
+            for from in 0..number_of_nodes {
+                for into in 0..number_of_nodes {
+                    if let (Some(first), Some(second)) = (matrix[from][via], matrix[via][into]) {
+                        if matrix[from][into].is_none_or(|distance| first + second < distance) {
+                            matrix[from][into] = Some(first + second);
+                        }
+                    }
+                }
+            }
+        }
+        matrix
+    }
+
+    #[test]
+    fn test_johnson() {
+        let arcs = vec![
+            (0, 1, 4),
+            (0, 2, 2),
+            (2, 1, -3),
+            (1, 3, 2),
+            (3, 4, -1),
+            (2, 4, 5),
+            (4, 0, 3),
+            (5, 4, -2),
+        ];
+        let network = network_factory::<i64>(arcs.clone());
+        let matrix = johnson(&network).unwrap();
+        assert_eq!(matrix, floyd_warshall_reference(&arcs, 6));
+        assert_eq!(matrix[0][1], Some(-1));
+        assert_eq!(matrix[5][0], Some(1));
+        assert_eq!(matrix[0][5], None);
+    }
+
+    #[test]
+    fn test_dijkstra_on_reweighted_network() {
+        // 0 -> 1 -> 2 weighs -2 and 0 -> 2 weighs 1
+        let arcs = vec![(0, 1, -1), (1, 2, -1), (0, 2, 1), (2, 3, 2)];
+        let network = network_factory::<i64>(arcs.clone());
+        let potentials = potentials(&network).unwrap();
+        assert_eq!(potentials, vec![0, -1, -2, 0]);
+
+        let reweight = |&(from, into, weight): &(NodeId, NodeId, i64)| {
+            (from, into, weight + potentials[from] - potentials[into])
+        };
+        let mut reweighted = network_factory::<i64>(arcs.iter().map(reweight).collect());
+        // both arcs of -1 become 0, while the arc of 1 becomes 3
+        let weights: Vec<i64> = reweighted.iter_arcs().map(|(_, _, _, arc)| arc.weight()).collect();
+        assert_eq!(weights, vec![0, 0, 3, 0]);
+
+        dijkstra(&mut reweighted, 0).unwrap();
+        let distances: Vec<i64> = (0..4)
+            .map(|into| {
+                let distance = reweighted.data_of_node(into).unwrap().distance();
+                distance - potentials[0] + potentials[into]
+            })
+            .collect();
+        let mut expected = network.clone();
+        bellman_ford(&mut expected, 0).unwrap();
+        assert_eq!(distances, expected.distances());
+        assert_eq!(distances, vec![0, -1, -2, 0]);
+        assert_eq!(reweighted.data_of_node(2).unwrap().predecessor(), Some(1));
+    }
+
+    #[test]
+    fn test_johnson_negative_cycle() {
+        // 1 -> 2 -> 3 -> 1 weighs -1 in total, and is not reachable from 4
+        let network = network_factory::<i64>(vec![
+            (0, 1, 1),
+            (1, 2, 2),
+            (2, 3, -4),
+            (3, 1, 1),
+            (3, 4, 1),
+        ]);
+        let error = johnson(&network).unwrap_err();
+        assert!([1, 2, 3].contains(&error.node));
+    }
+}
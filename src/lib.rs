@@ -1,3 +1,8 @@
 pub mod mutable_heap;
+pub mod pairing_heap;
 pub mod graph;
-pub mod dijkstra;
\ No newline at end of file
+pub mod weight;
+pub mod dijkstra;
+pub mod bellman_ford;
+pub mod johnson;
+pub mod io;
\ No newline at end of file
@@ -11,6 +11,6 @@ fn main() {
         (4, 5, 2),
         (5, 3, 2),
     ]);
-    dijkstra(&mut network, 0);
+    dijkstra(&mut network, 0).unwrap();
     println!("{}", network);
 }
@@ -1,11 +1,22 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 type HeapNodeId = usize;
 
 // in this code, `floating` is used to represent the state of a node that is not a child of any other node nor set in the rank_id_cache.
+//
+// invariant between the calls of the public methods (see `validate`): every root is "landed" (in rank_id_cache under
+// its rank) or in floating_ids, exactly once, and min_id_cache is a root with the minimum key.
+// inside `cut`, the detached nodes are floating only in a local vector until `land_floating_nodes` lands them.
+// the methods take `&mut self`, so no other call can observe that window, and every method lands all of them
+// before it returns.
 
-pub trait MutableHeap<K: Ord> {
+pub trait MutableHeap<K> {
     /// push a new node with key `key` into the heap
     /// and return the `id` of the node
     fn push(&mut self, key: K) -> HeapNodeId;
@@ -17,6 +28,59 @@ pub trait MutableHeap<K: Ord> {
     fn modify(&mut self, id: HeapNodeId, new_key: K);
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeapError {
+    /// no node with the id is in the heap (never pushed, or already popped)
+    NotFound(HeapNodeId),
+    /// the new key moves the node in the opposite direction of the method (or does not move it)
+    WrongDirection(HeapNodeId),
+}
+impl Display for HeapError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            HeapError::NotFound(id) => write!(f, "node {} is not in the heap", id),
+            HeapError::WrongDirection(id) => {
+                write!(f, "key of node {} is moved in the wrong direction", id)
+            }
+        }
+    }
+}
+impl std::error::Error for HeapError {}
+
+/// decides which key comes out first. the heap pops the key which is the smallest by `compare`.
+pub trait Comparator<K> {
+    fn compare(&self, a: &K, b: &K) -> Ordering;
+}
+
+/// pops the minimum first (natural order of `Ord`)
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MinFirst;
+impl<K: Ord> Comparator<K> for MinFirst {
+    fn compare(&self, a: &K, b: &K) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// pops the maximum first (reversed order of `Ord`)
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MaxFirst;
+impl<K: Ord> Comparator<K> for MaxFirst {
+    fn compare(&self, a: &K, b: &K) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+/// any closure can be a comparator, so that the keys do not need to implement `Ord`
+impl<K, F: Fn(&K, &K) -> Ordering> Comparator<K> for F {
+    fn compare(&self, a: &K, b: &K) -> Ordering {
+        self(a, b)
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct Node<K> {
     /// primitive data
     key: K,
@@ -31,26 +95,104 @@ impl<K> Node<K> {
     }
 }
 
-pub struct FibonacciHeap<K: Ord> {
+/// with the `serde` feature, the heap is serialized as it is (including the caches),
+/// so that ids of the nodes are still valid after deserialization.
+/// cloning is a deep copy in the same manner: the clone is independent, and ids tracked for the original are valid for it.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FibonacciHeap<K, C = MinFirst> {
     /// primitive data
-    id_node_map: HashMap<HeapNodeId, Node<K>>,
+    /// arena of the nodes indexed by their id. popped nodes are left as `None`, so that ids are never reused
+    /// unless `free_ids` is enabled.
+    nodes: Vec<Option<Node<K>>>,
+    /// ids of the popped nodes to be handed out again by push. `None` if ids are not reused.
+    free_ids: Option<Vec<HeapNodeId>>,
     /// state
-    id_provider: usize,
+    /// pushed roots which are not landed yet. they are landed (consolidated) all together on pop.
+    floating_ids: Vec<HeapNodeId>,
     /// cache
     min_id_cache: Option<HeapNodeId>,
     rank_id_cache: HashMap<usize, HeapNodeId>,
+    /// number of the nodes in the heap. `nodes.len()` counts popped ones as well.
+    len: usize,
+    /// order of the keys. "min" in this code means the first one in this order.
+    comparator: C,
+}
+
+/// heap which pops the maximum first
+pub type MaxFibonacciHeap<K> = FibonacciHeap<K, MaxFirst>;
+
+impl<K, C: Comparator<K> + Default> Default for FibonacciHeap<K, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+/// push every key. a push only appends a root, so this is O(1) per key,
+/// and the trees are consolidated on the next pop.
+/// the ids are not returned: use `push` one by one to track them.
+impl<K, C: Comparator<K>> Extend<K> for FibonacciHeap<K, C> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.nodes.reserve(iter.size_hint().0);
+        for key in iter {
+            self.push(key);
+        }
+    }
 }
 
-/// general methods (methods that are required for multiple MutableHeap methods)
 impl<K: Ord> FibonacciHeap<K> {
+    /// min-heap. ids are never reused, so the arena grows by one slot on every push, popped or not:
+    /// its memory follows the number of pushes over the lifetime of the heap, not the number of nodes in it.
+    /// for long-running heaps, use `new_reusing_ids` or replace the heap once it is empty.
     pub fn new() -> Self {
+        Self::with_comparator(MinFirst)
+    }
+    /// min-heap which reuses the ids of popped nodes. see `with_comparator_reusing_ids` for the contract.
+    pub fn new_reusing_ids() -> Self {
+        Self::with_comparator_reusing_ids(MinFirst)
+    }
+}
+
+impl<K: Ord> FibonacciHeap<K, MaxFirst> {
+    /// heap which pops the maximum first. same as `MaxFibonacciHeap::default()`.
+    pub fn new_max() -> Self {
+        Self::with_comparator(MaxFirst)
+    }
+}
+
+/// general methods (methods that are required for multiple MutableHeap methods)
+impl<K, C: Comparator<K>> FibonacciHeap<K, C> {
+    /// heap which pops the smallest key by `comparator` first. `K` does not need to be `Ord`.
+    pub fn with_comparator(comparator: C) -> Self {
         FibonacciHeap {
-            id_node_map: HashMap::new(),
-            id_provider: 0,
+            nodes: Vec::new(),
+            free_ids: None,
+            floating_ids: Vec::new(),
             min_id_cache: None,
             rank_id_cache: HashMap::new(),
+            len: 0,
+            comparator,
         }
     }
+    /// same as `with_comparator`, but the ids of popped nodes are reused by later pushes, so that the arena
+    /// stays as large as the heap has ever been instead of growing with every push.
+    /// the caller must forget an id once its node is popped: a later push may hand it out for another node,
+    /// and `modify` with the stale id would silently move that node instead.
+    pub fn with_comparator_reusing_ids(comparator: C) -> Self {
+        FibonacciHeap {
+            free_ids: Some(Vec::new()),
+            ..Self::with_comparator(comparator)
+        }
+    }
+    /// true if the ids of popped nodes are reused (see `with_comparator_reusing_ids`)
+    pub fn reuses_ids(&self) -> bool {
+        self.free_ids.is_some()
+    }
+    /// true if `a` should come out before `b`
+    fn precedes(&self, a: &K, b: &K) -> bool {
+        self.comparator.compare(a, b) == Ordering::Less
+    }
     /// take the ids of two heap heads and join them
     /// returns id of head of the merged heap
     fn merge(&mut self, heap1: HeapNodeId, heap2: HeapNodeId) -> HeapNodeId {
@@ -59,44 +201,50 @@ impl<K: Ord> FibonacciHeap<K> {
         // heap1 and heap2 are different
         debug_assert!(heap1 != heap2);
 
+        let is_heap1_cached = Some(heap1) == self.min_id_cache;
+        let [node1, node2] = self.nodes.get_disjoint_mut([heap1, heap2]).unwrap();
+        let (node1, node2) = (node1.as_mut().unwrap(), node2.as_mut().unwrap());
+
         // BOUNDARY: if both heaps are the minimum, cached one should be the parent.
-        let (smaller, larger): (HeapNodeId, HeapNodeId) = if Some(heap1) == self.min_id_cache || self
-            .id_node_map
-            .get(&heap1)
-            .unwrap()
-            .key
-            < self.id_node_map.get(&heap2).unwrap().key
-        {
-            (heap1, heap2)
-        } else {
-            (heap2, heap1)
+        // the cache may be stale during increase_key, so it must not win over a smaller one.
+        let heap1_is_parent = match self.comparator.compare(&node1.key, &node2.key) {
+            Ordering::Less => true,
+            Ordering::Equal => is_heap1_cached,
+            Ordering::Greater => false,
         };
-
-        let smaller_node = self.id_node_map.get_mut(&smaller).unwrap();
-        smaller_node.children.push(larger);
-        let larger_node = self.id_node_map.get_mut(&larger).unwrap();
-        larger_node.parent = Some(smaller);
-
-        smaller
+        if heap1_is_parent {
+            node1.children.push(heap2);
+            node2.parent = Some(heap1);
+            heap1
+        } else {
+            node2.children.push(heap1);
+            node1.parent = Some(heap2);
+            heap2
+        }
     }
-    /// put the heap keeping fibonacci-heap property. also update rank_id_cache
+    /// put the heap keeping fibonacci-heap property. also update rank_id_cache and min_id_cache
     fn put(&mut self, heap: HeapNodeId, rank: usize) {
         debug_assert!(self.is_valid_as_root(&heap));
         // given rank is consistent with the rank of the heap
         // note: although rank can be calculated by heap, it is given as an argument for efficiency
-        debug_assert!(self.id_node_map.get(&heap).unwrap().rank() == rank);
+        debug_assert!(self.nodes[heap].as_ref().unwrap().rank() == rank);
 
-        let cached = self.rank_id_cache.remove(&rank);
-        if let Some(cached) = cached {
-            let merged_heap = self.merge(heap, cached);
-            self.put(merged_heap, rank + 1);
-        } else {
-            self.rank_id_cache.insert(rank, heap);
+        match self.rank_id_cache.entry(rank) {
+            Entry::Occupied(cached) => {
+                let cached = cached.remove();
+                let merged_heap = self.merge(heap, cached);
+                self.put(merged_heap, rank + 1);
+            }
+            Entry::Vacant(vacant) => {
+                vacant.insert(heap);
+                // every root ends up here, so the minimum can be tracked without an extra pass
+                self.update_min_id_cache(heap);
+            }
         }
     }
     fn land_floating_nodes(&mut self, floating: Vec<HeapNodeId>) {
         for node_id in floating.into_iter() {
-            let rank = self.id_node_map.get(&node_id).unwrap().rank();
+            let rank = self.nodes[node_id].as_ref().unwrap().rank();
             self.put(node_id, rank);
         }
     }
@@ -107,25 +255,91 @@ impl<K: Ord> FibonacciHeap<K> {
             return;
         }
         let min_id = self.min_id_cache.unwrap();
-        if self.id_node_map.get(&id).unwrap().key < self.id_node_map.get(&min_id).unwrap().key {
+        let key = &self.nodes[id].as_ref().unwrap().key;
+        let min_key = &self.nodes[min_id].as_ref().unwrap().key;
+        if self.precedes(key, min_key) {
             self.min_id_cache = Some(id);
         }
     }
     /// debug method. returns true if the given id is valid as a root. root means the node that is not a child of any other node
     fn is_valid_as_root(&self, root: &HeapNodeId) -> bool {
-        self.id_node_map
-            .get(root)
-            .is_some_and(|root| root.parent.is_none())
+        self.contains(*root) && self.nodes[*root].as_ref().unwrap().parent.is_none()
+    }
+    /// returns true if the node with the id is in the heap
+    fn contains(&self, id: HeapNodeId) -> bool {
+        self.nodes.get(id).is_some_and(Option::is_some)
+    }
+    /// debug method. returns true if the node is a child, or a root which is landed or in floating_ids,
+    /// i.e. it is not left floating in the middle of an operation.
+    fn is_landed(&self, id: HeapNodeId) -> bool {
+        let node = self.nodes[id].as_ref().unwrap();
+        node.parent.is_some()
+            || self.rank_id_cache.get(&node.rank()) == Some(&id)
+            || self.floating_ids.contains(&id)
+    }
+    /// test method. checks the whole structure and panics on the first violation. O(n).
+    /// returns true, so that it can be used in `assert!`.
+    #[cfg(test)]
+    fn validate(&self) -> bool {
+        let mut roots = Vec::new();
+        let mut len = 0;
+        for (id, node) in self.nodes.iter().enumerate() {
+            let Some(node) = node else { continue };
+            len += 1;
+            match node.parent {
+                Some(parent_id) => {
+                    let parent = self.nodes[parent_id].as_ref().expect("parent is popped");
+                    assert!(parent.children.contains(&id), "{} is not a child of its parent", id);
+                    assert!(!self.precedes(&node.key, &parent.key), "{} precedes its parent", id);
+                }
+                None => {
+                    assert!(!node.shrinked, "root {} is marked", id);
+                    roots.push(id);
+                }
+            }
+            for &child_id in node.children.iter() {
+                let child = self.nodes[child_id].as_ref().expect("child is popped");
+                assert_eq!(child.parent, Some(id), "parent of {} is not {}", child_id, id);
+            }
+        }
+        assert_eq!(len, self.len);
+        if let Some(free_ids) = self.free_ids.as_ref() {
+            assert!(free_ids.iter().all(|&id| self.nodes[id].is_none()), "a free id is in use");
+            assert_eq!(free_ids.len() + len, self.nodes.len(), "a popped id is not free");
+        }
+
+        // every root is landed under its rank or floating, exactly once
+        for (&rank, &id) in self.rank_id_cache.iter() {
+            let node = self.nodes[id].as_ref().unwrap();
+            assert_eq!(node.rank(), rank, "{} is landed on a wrong rank", id);
+        }
+        let mut registered = self.root_ids();
+        registered.sort();
+        assert_eq!(registered, roots, "roots are not landed nor floating exactly once");
+
+        match self.min_id_cache {
+            Some(min_id) => {
+                assert!(roots.contains(&min_id), "min_id_cache {} is not a root", min_id);
+                let min_key = &self.nodes[min_id].as_ref().unwrap().key;
+                assert!(roots
+                    .iter()
+                    .all(|&id| !self.precedes(&self.nodes[id].as_ref().unwrap().key, min_key)));
+            }
+            None => assert_eq!(self.len, 0),
+        }
+        true
     }
 }
 
 // following three blocks are separated so that it is easier to understand. there is no more reason to do so.
 
 /// to push
-impl<K: Ord> FibonacciHeap<K> {
+impl<K, C: Comparator<K>> FibonacciHeap<K, C> {
     fn provide_id(&mut self) -> HeapNodeId {
-        self.id_provider += 1;
-        self.id_provider
+        self.free_ids
+            .as_mut()
+            .and_then(Vec::pop)
+            .unwrap_or(self.nodes.len())
     }
     fn make_and_link_node(&mut self, id: HeapNodeId, key: K) {
         // make brand new node with id
@@ -135,80 +349,79 @@ impl<K: Ord> FibonacciHeap<K> {
             children: vec![],
             shrinked: false,
         };
-        // link id and node
-        self.id_node_map.insert(id, node);
+        // link id and node. a reused id takes over the slot of a popped node
+        if id == self.nodes.len() {
+            self.nodes.push(Some(node));
+        } else {
+            debug_assert!(self.nodes[id].is_none());
+            self.nodes[id] = Some(node);
+        }
+        self.len += 1;
     }
 }
 
 /// to pop
-impl<K: Ord> FibonacciHeap<K> {
-    /// returns true if the assertion is satisfied
-    /// only used for debugging
-    fn pop_assertions(&self) -> bool {
+impl<K, C: Comparator<K>> FibonacciHeap<K, C> {
+    /// panics if the minimum is not ready to be popped.
+    /// only compiled with debug assertions, so that neither the call nor the scan of the caches is left in release.
+    #[cfg(debug_assertions)]
+    fn pop_assertions(&self) {
         if let Some(min_id_cache) = self.min_id_cache {
-            let min_node = self.id_node_map.get(&min_id_cache).unwrap();
+            let min_node = self.nodes[min_id_cache].as_ref().unwrap();
             assert!(self.is_valid_as_root(&min_id_cache));
-            assert!(self.rank_id_cache.get(&min_node.rank()).is_some());
-            assert!(self
-                .rank_id_cache
-                .get(&min_node.rank())
-                .is_some_and(|&id| &id == &min_id_cache));
+            // the minimum is either landed or floating
+            assert!(
+                self.rank_id_cache
+                    .get(&min_node.rank())
+                    .is_some_and(|&id| id == min_id_cache)
+                    || self.floating_ids.contains(&min_id_cache)
+            );
         }
-        return true;
     }
     fn pop_min_node_from_cache(&mut self) -> Option<HeapNodeId> {
         match self.min_id_cache.take() {
             Some(min_id) => {
-                let min_node = self.id_node_map.get(&min_id).unwrap();
-                let min_id_on_rank_cache = self.rank_id_cache.remove(&min_node.rank());
-                assert_eq!(min_id_on_rank_cache.unwrap(), min_id);
+                let min_node = self.nodes[min_id].as_ref().unwrap();
+                match self.rank_id_cache.entry(min_node.rank()) {
+                    Entry::Occupied(landed) if *landed.get() == min_id => {
+                        landed.remove();
+                    }
+                    _ => {
+                        let position = self.floating_ids.iter().position(|&id| id == min_id);
+                        self.floating_ids.swap_remove(position.unwrap());
+                    }
+                }
                 Some(min_id)
             }
             None => None,
         }
     }
-    /// cut off children from given node.
-    fn release_children(&mut self, id: HeapNodeId) {
-        let node = self.id_node_map.get_mut(&id).unwrap();
-        let children = node.children.clone();
-        node.children.clear();
+    /// cut off children from given node and returns them as floating nodes.
+    fn release_children(&mut self, id: HeapNodeId) -> Vec<HeapNodeId> {
+        let node = self.nodes[id].as_mut().unwrap();
+        let children = std::mem::take(&mut node.children);
         for child_id in children.iter() {
-            let child = self.id_node_map.get_mut(child_id).unwrap();
+            let child = self.nodes[*child_id].as_mut().unwrap();
             child.parent = None;
             child.shrinked = false;
         }
-        self.land_floating_nodes(children);
-    }
-    fn rebuild_min_id_cache(&mut self) {
-        let mut min_id = None;
-        for (_, &id) in self.rank_id_cache.iter() {
-            if let Some(min_id_unwrapped) = min_id {
-                let current_min = self.id_node_map.get(&min_id_unwrapped).unwrap();
-                let candidate = self.id_node_map.get(&id).unwrap();
-                if current_min.key > candidate.key {
-                    min_id = Some(id.clone());
-                }
-            } else {
-                min_id = Some(id);
-            }
-        }
-        self.min_id_cache = min_id;
+        children
     }
 }
 
 /// to modify
-impl<K: Ord> FibonacciHeap<K> {
+impl<K, C: Comparator<K>> FibonacciHeap<K, C> {
     /// detach the child from its parent, mark its parent and do cascading cut if necessary.
     /// returns the floating nodes.
     fn cut(&mut self, parent: HeapNodeId, child: HeapNodeId) {
         // detach the child from its parent
-        let child_node = self.id_node_map.get_mut(&child).unwrap();
+        let child_node = self.nodes[child].as_mut().unwrap();
         child_node.parent = None;
         child_node.shrinked = false;
 
         let mut floating = vec![child];
 
-        let parent_node = self.id_node_map.get_mut(&parent).unwrap();
+        let parent_node = self.nodes[parent].as_mut().unwrap();
         let needs_cascading_cut = parent_node.shrinked; // before mutate parent_node, note the state
         if parent_node.parent.is_some() {
             // if the parent has a parent, the parent should be marked as shrinked to do cascading cut
@@ -218,10 +431,9 @@ impl<K: Ord> FibonacciHeap<K> {
             // if the parent remains in rank_id_cache, it becomes inconsistent with the actual rank of the node.
             // to avoid this, remove it from rank_id_cache if any.
             let former_rank_of_parent = parent_node.rank();
-            let maybe_parent = self.rank_id_cache.get(&former_rank_of_parent);
-            if let Some(maybe_parent) = maybe_parent {
-                if maybe_parent == &parent {
-                    self.rank_id_cache.remove(&former_rank_of_parent);
+            if let Entry::Occupied(maybe_parent) = self.rank_id_cache.entry(former_rank_of_parent) {
+                if maybe_parent.get() == &parent {
+                    maybe_parent.remove();
                     floating.push(parent);
                 }
             }
@@ -237,65 +449,200 @@ impl<K: Ord> FibonacciHeap<K> {
         self.land_floating_nodes(floating);
     }
     fn heapify_between(&mut self, parent: HeapNodeId, child: HeapNodeId) {
-        let parent_node = self.id_node_map.get(&parent).unwrap();
-        let child_node = self.id_node_map.get(&child).unwrap();
-        if parent_node.key > child_node.key {
+        let parent_node = self.nodes[parent].as_ref().unwrap();
+        let child_node = self.nodes[child].as_ref().unwrap();
+        if self.precedes(&child_node.key, &parent_node.key) {
             self.cut(parent, child);
         }
     }
+    /// find the minimum among all the roots from scratch. O(number of roots).
+    fn rebuild_min_id_cache(&mut self) {
+        self.min_id_cache = None;
+        for id in self.root_ids() {
+            self.update_min_id_cache(id);
+        }
+    }
+    /// move the node towards the front (i.e. make the key smaller for a min-first heap).
+    /// only the node and its parent can violate the heap property, so it is cut off from the parent if necessary.
+    /// amortized O(1).
+    pub fn decrease_key(&mut self, id: HeapNodeId, new_key: K) -> Result<(), HeapError> {
+        let node = self.nodes.get_mut(id).and_then(Option::as_mut);
+        let node = node.ok_or(HeapError::NotFound(id))?;
+        if self.comparator.compare(&new_key, &node.key) != Ordering::Less {
+            return Err(HeapError::WrongDirection(id));
+        }
+        node.key = new_key;
+
+        // the cache must only point to a root. a node which is cut off is landed as a root,
+        // and the cache is updated on landing. a node which stays a child does not come before its root.
+        match node.parent {
+            Some(parent_id) => self.heapify_between(parent_id, id),
+            None => self.update_min_id_cache(id),
+        }
+        Ok(())
+    }
+    /// move the node towards the back (i.e. make the key larger for a min-first heap).
+    /// the children which come before the node are cut off.
+    /// if the node was the minimum, the minimum is searched again among the roots. O(log n) amortized.
+    pub fn increase_key(&mut self, id: HeapNodeId, new_key: K) -> Result<(), HeapError> {
+        let node = self.nodes.get_mut(id).and_then(Option::as_mut);
+        let node = node.ok_or(HeapError::NotFound(id))?;
+        if self.comparator.compare(&new_key, &node.key) != Ordering::Greater {
+            return Err(HeapError::WrongDirection(id));
+        }
+        node.key = new_key;
+        // note it before the cuts, as landing the children may update the cache
+        let was_min = self.min_id_cache == Some(id);
+
+        // only the violating children are collected
+        let node = self.nodes[id].as_ref().unwrap();
+        let violating_children: Vec<HeapNodeId> = node
+            .children
+            .iter()
+            .copied()
+            .filter(|child_id| {
+                self.precedes(&self.nodes[*child_id].as_ref().unwrap().key, &node.key)
+            })
+            .collect();
+        for child_id in violating_children {
+            self.cut(id, child_id);
+        }
+
+        if was_min {
+            self.rebuild_min_id_cache();
+        }
+        Ok(())
+    }
 }
 
-impl<K: Ord> MutableHeap<K> for FibonacciHeap<K> {
+impl<K, C: Comparator<K>> MutableHeap<K> for FibonacciHeap<K, C> {
     fn push(&mut self, key: K) -> HeapNodeId {
         let id = self.provide_id();
         self.make_and_link_node(id, key);
         self.update_min_id_cache(id);
-        self.put(id, 0);
+        // landing is deferred to pop, so that push is O(1)
+        self.floating_ids.push(id);
         id
     }
     fn pop(&mut self) -> Option<(HeapNodeId, K)> {
-        debug_assert!(self.pop_assertions());
+        #[cfg(debug_assertions)]
+        self.pop_assertions();
 
-        let min_id = self.pop_min_node_from_cache();
-        if min_id.is_none() {
-            return None;
-        }
-        let min_id = min_id.unwrap();
-
-        self.release_children(min_id);
+        let min_id = self.pop_min_node_from_cache()?;
 
-        self.rebuild_min_id_cache();
+        // all the roots are landed again together with the released children.
+        // put finds the new minimum on the way, so that no separate pass over the roots is needed.
+        let mut floating = self.release_children(min_id);
+        floating.append(&mut self.floating_ids);
+        floating.extend(self.rank_id_cache.drain().map(|(_, id)| id));
+        self.land_floating_nodes(floating);
 
-        return match self.id_node_map.remove(&min_id) {
+        self.len -= 1;
+        if let Some(free_ids) = self.free_ids.as_mut() {
+            free_ids.push(min_id);
+        }
+        match self.nodes[min_id].take() {
             Some(min_node) => Some((min_id, min_node.key)),
             None => panic!("minimum node is unexpectedly removed in a way"),
-        };
+        }
     }
     fn get_min(&self) -> Option<HeapNodeId> {
-        return self.min_id_cache;
+        self.min_id_cache
     }
     fn modify(&mut self, id: HeapNodeId, new_key: K) {
         // if client not tracks the id properly, they may try to modify a non-existing node
-        assert!(self.id_node_map.contains_key(&id));
+        assert!(self.contains(id));
+        debug_assert!(self.is_landed(id));
 
-        let node = self.id_node_map.get_mut(&id).unwrap();
-        node.key = new_key;
-        self.update_min_id_cache(id);
+        let node = self.nodes[id].as_mut().unwrap();
+        let result = match self.comparator.compare(&new_key, &node.key) {
+            Ordering::Less => self.decrease_key(id, new_key),
+            Ordering::Greater => self.increase_key(id, new_key),
+            Ordering::Equal => {
+                // fast path: the structure and the caches do not change.
+                // the key is still replaced, as an equal key by the comparator may differ in other fields.
+                node.key = new_key;
+                Ok(())
+            }
+        };
+        debug_assert!(result.is_ok());
+    }
+}
 
-        // make sure the node satisfies the heap property
-        // between the node and its parent
-        let node = self.id_node_map.get(&id).unwrap();
-        let parent_id = node.parent;
-        if let Some(parent_id) = parent_id {
-            self.heapify_between(parent_id, id);
+/// utilities
+impl<K, C: Comparator<K>> FibonacciHeap<K, C> {
+    /// get the minimum `key` itself. O(1).
+    pub fn min_key(&self) -> Option<&K> {
+        self.min_id_cache
+            .map(|min_id| &self.nodes[min_id].as_ref().unwrap().key)
+    }
+    /// pop only if the front key satisfies `pred`. otherwise the heap is left untouched and `None` is returned.
+    /// `pred` is not called on an empty heap.
+    pub fn pop_if(&mut self, pred: impl FnOnce(&K) -> bool) -> Option<(HeapNodeId, K)> {
+        if pred(self.min_key()?) {
+            self.pop()
+        } else {
+            None
         }
-
-        // between the node and its children
-        let node = self.id_node_map.get(&id).unwrap();
-        for child_id in node.children.clone() {
-            self.heapify_between(id, child_id);
+    }
+    /// number of the nodes in the heap. O(1).
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// iterate all the nodes in the heap in arbitrary order (by id, as it happens).
+    /// use `into_sorted_vec` for the sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = (HeapNodeId, &K)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(id, node)| Some((id, &node.as_ref()?.key)))
+    }
+    /// ids of the roots, i.e. the heads of the trees in the forest, in arbitrary order.
+    /// right after a pop, the roots have distinct ranks, so there are O(log n) of them.
+    /// pushes after that add a root each, until the next pop consolidates them.
+    pub fn root_ids(&self) -> Vec<HeapNodeId> {
+        self.rank_id_cache
+            .values()
+            .chain(self.floating_ids.iter())
+            .copied()
+            .collect()
+    }
+    /// parent of the node in the forest, e.g. to render it. `None` for a root, and for a node not in the heap.
+    pub fn node_parent(&self, id: HeapNodeId) -> Option<HeapNodeId> {
+        self.nodes.get(id)?.as_ref()?.parent
+    }
+    /// children of the node in the forest. empty for a leaf, and for a node not in the heap.
+    pub fn node_children(&self, id: HeapNodeId) -> Vec<HeapNodeId> {
+        match self.nodes.get(id).and_then(Option::as_ref) {
+            Some(node) => node.children.clone(),
+            None => Vec::new(),
         }
     }
+    /// number of the trees in the forest
+    pub fn tree_count(&self) -> usize {
+        self.rank_id_cache.len() + self.floating_ids.len()
+    }
+    /// release the spare capacity of the internal buffers (e.g. after a dijkstra run popped everything).
+    /// the slots of the popped nodes are kept, so that ids are never reused (unless `reuses_ids`):
+    /// this does not reclaim the one slot per push the arena has grown by (see `new`).
+    pub fn shrink_to_fit(&mut self) {
+        self.nodes.shrink_to_fit();
+        if let Some(free_ids) = self.free_ids.as_mut() {
+            free_ids.shrink_to_fit();
+        }
+        for node in self.nodes.iter_mut().flatten() {
+            node.children.shrink_to_fit();
+        }
+        self.floating_ids.shrink_to_fit();
+        self.rank_id_cache.shrink_to_fit();
+    }
+    /// pop all the nodes in order. O(n log n).
+    pub fn into_sorted_vec(mut self) -> Vec<(HeapNodeId, K)> {
+        std::iter::from_fn(|| self.pop()).collect()
+    }
 }
 
 impl<K: Display> Display for Node<K> {
@@ -304,28 +651,28 @@ impl<K: Display> Display for Node<K> {
         if self.shrinked {
             write!(f, "*")?;
         }
-        write!(f, "\n")
+        writeln!(f)
     }
 }
 
-impl<K: Display + Ord> FibonacciHeap<K> {
+impl<K: Display, C> FibonacciHeap<K, C> {
     fn display_tree(&self, id: HeapNodeId, depth: usize, f: &mut Formatter) -> fmt::Result {
-        let node = self.id_node_map.get(&id).unwrap();
+        let node = self.nodes[id].as_ref().unwrap();
         for _ in 0..depth {
             write!(f, "| ")?;
         }
         write!(f, "{}:{}", id, node)?;
         for child_id in node.children.iter() {
-            self.display_tree(child_id.clone(), depth + 1, f)?;
+            self.display_tree(*child_id, depth + 1, f)?;
         }
         Ok(())
     }
 }
 
-impl<K: Display + Ord> Display for FibonacciHeap<K> {
+impl<K: Display, C> Display for FibonacciHeap<K, C> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        for (_, id) in self.rank_id_cache.iter() {
-            self.display_tree(id.clone(), 0, f)?;
+        for id in self.rank_id_cache.values().chain(self.floating_ids.iter()) {
+            self.display_tree(*id, 0, f)?;
         }
         write!(f, "min_id_cache: {:?}", self.min_id_cache)
     }
@@ -334,6 +681,7 @@ impl<K: Display + Ord> Display for FibonacciHeap<K> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_mutable_heap() {
@@ -394,4 +742,454 @@ mod test {
             previous_key = key;
         }
     }
+
+    #[test]
+    fn test_min_key() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        assert_eq!(heap.min_key(), None);
+        let keys = [5, 3, 8, 1, 9, 2];
+        let ids: Vec<HeapNodeId> = keys.iter().map(|&key| heap.push(key)).collect();
+        heap.modify(ids[4], 0);
+        while let Some(&min_key) = heap.min_key() {
+            let (_, key) = heap.pop().unwrap();
+            assert_eq!(min_key, key);
+        }
+        assert!(heap.pop().is_none());
+    }
+
+    #[test]
+    fn test_pop_if() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        assert_eq!(heap.pop_if(|_| panic!("called on an empty heap")), None);
+        let ids: Vec<HeapNodeId> = [5, 3, 8].iter().map(|&key| heap.push(key)).collect();
+
+        // skipped
+        assert_eq!(heap.pop_if(|&key| key > 3), None);
+        assert_eq!(heap.len(), 3);
+        assert_eq!(heap.min_key(), Some(&3));
+        // taken
+        assert_eq!(heap.pop_if(|&key| key <= 3), Some((ids[1], 3)));
+        assert_eq!(heap.len(), 2);
+        assert_eq!(heap.pop_if(|&key| key == 5), Some((ids[0], 5)));
+        assert_eq!(heap.into_sorted_vec(), vec![(ids[2], 8)]);
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        heap.push(4);
+        heap.extend([7, 1, 9, 4, 0]);
+        heap.extend(Vec::new());
+        assert_eq!(heap.len(), 6);
+        assert_eq!(heap.min_key(), Some(&0));
+        let keys: Vec<i32> = heap.into_sorted_vec().into_iter().map(|(_, key)| key).collect();
+        assert_eq!(keys, vec![0, 1, 4, 4, 7, 9]);
+
+        let mut heap = MaxFibonacciHeap::default();
+        heap.extend(random_keys(3, 100));
+        let keys: Vec<i32> = heap.into_sorted_vec().into_iter().map(|(_, key)| key).collect();
+        let mut expected = random_keys(3, 100);
+        expected.sort_by(|a, b| b.cmp(a));
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn test_id_reuse() {
+        // ids are never reused by default
+        let mut heap = FibonacciHeap::<i32>::new();
+        assert!(!heap.reuses_ids());
+        let first = heap.push(3);
+        heap.push(5);
+        assert_eq!(heap.pop(), Some((first, 3)));
+        assert_eq!(heap.push(1), 2);
+
+        let mut heap = FibonacciHeap::<i32>::new_reusing_ids();
+        assert!(heap.reuses_ids());
+        let ids: Vec<HeapNodeId> = [3, 5, 4].iter().map(|&key| heap.push(key)).collect();
+        assert_eq!(heap.pop(), Some((ids[0], 3)));
+        assert_eq!(heap.pop(), Some((ids[2], 4)));
+        // the last popped id comes back first
+        assert_eq!(heap.push(1), ids[2]);
+        assert_eq!(heap.push(9), ids[0]);
+        assert_eq!(heap.push(7), 3);
+        assert!(heap.validate());
+
+        // the arena stays as large as the heap has ever been
+        for round in 0..100 {
+            let id = heap.push(round);
+            heap.modify(id, -round);
+            assert!(heap.validate());
+            assert_eq!(heap.pop(), Some((id, -round)));
+        }
+        assert_eq!(heap.nodes.len(), 5);
+        let keys: Vec<i32> = heap.into_sorted_vec().into_iter().map(|(_, key)| key).collect();
+        assert_eq!(keys, vec![1, 5, 7, 9]);
+    }
+
+    /// xorshift to generate reproducible "random" keys without any dependency
+    fn random_keys(mut seed: u64, len: usize) -> Vec<i32> {
+        (0..len)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                (seed % 1000) as i32
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_into_sorted_vec() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        let keys = random_keys(42, 500);
+        let ids: Vec<HeapNodeId> = keys.iter().map(|&key| heap.push(key)).collect();
+        // some pops and modifications in between so that the heap has trees
+        heap.pop();
+        heap.modify(ids[100], -5);
+        heap.modify(ids[200], -3);
+
+        let sorted = heap.into_sorted_vec();
+        assert_eq!(sorted.len(), keys.len() - 1);
+        assert!(sorted.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert_eq!(sorted[0], (ids[100], -5));
+        assert_eq!(sorted[1], (ids[200], -3));
+    }
+
+    #[test]
+    fn test_max_heap() {
+        let mut heap = MaxFibonacciHeap::<i32>::new_max();
+        let keys = random_keys(7, 200);
+        let ids: Vec<HeapNodeId> = keys.iter().map(|&key| heap.push(key)).collect();
+        assert_eq!(heap.min_key(), keys.iter().max());
+        heap.pop();
+        // "decrease" in max-first order is an increase of the key
+        heap.modify(ids[10], 5000);
+        heap.modify(ids[20], -1);
+        let sorted = heap.into_sorted_vec();
+        assert_eq!(sorted[0], (ids[10], 5000));
+        assert_eq!(sorted.last(), Some(&(ids[20], -1)));
+        assert!(sorted.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+
+    #[test]
+    fn test_with_comparator() {
+        // no Ord on purpose
+        struct Task {
+            name: &'static str,
+            priority: u32,
+        }
+        // by priority, which is not the order of the names
+        let mut heap =
+            FibonacciHeap::with_comparator(|a: &Task, b: &Task| a.priority.cmp(&b.priority));
+        for (name, priority) in [("a", 3), ("b", 1), ("c", 4), ("d", 2), ("e", 0)] {
+            heap.push(Task { name, priority });
+        }
+        let c = 2;
+        heap.modify(c, Task { name: "c", priority: 1 });
+        let names: Vec<&str> = heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|(_, task)| task.name)
+            .collect();
+        assert_eq!(names[0], "e");
+        assert_eq!(names[3], "d");
+        assert_eq!(names[4], "a");
+        // "b" and "c" have the same priority
+        assert!(names[1..3].contains(&"b") && names[1..3].contains(&"c"));
+    }
+
+    #[test]
+    fn test_decrease_and_increase_key() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        let ids: Vec<HeapNodeId> = (0..20).map(|key| heap.push(key * 10)).collect();
+        // make trees
+        assert_eq!(heap.pop(), Some((ids[0], 0)));
+
+        assert_eq!(heap.decrease_key(ids[15], 5), Ok(()));
+        assert_eq!(heap.min_key(), Some(&5));
+        // the minimum is increased, so the next one becomes the minimum
+        assert_eq!(heap.increase_key(ids[15], 1000), Ok(()));
+        assert_eq!(heap.min_key(), Some(&10));
+        assert_eq!(heap.increase_key(ids[1], 55), Ok(()));
+        assert_eq!(heap.min_key(), Some(&20));
+
+        // wrong direction
+        assert_eq!(heap.decrease_key(ids[2], 30), Err(HeapError::WrongDirection(ids[2])));
+        assert_eq!(heap.decrease_key(ids[2], 20), Err(HeapError::WrongDirection(ids[2])));
+        assert_eq!(heap.increase_key(ids[2], 10), Err(HeapError::WrongDirection(ids[2])));
+        // popped or never pushed
+        assert_eq!(heap.decrease_key(ids[0], -1), Err(HeapError::NotFound(ids[0])));
+        assert_eq!(heap.increase_key(100, 0), Err(HeapError::NotFound(100)));
+
+        let keys: Vec<i32> = heap.into_sorted_vec().into_iter().map(|(_, key)| key).collect();
+        let mut expected: Vec<i32> = (2..20).filter(|&key| key != 15).map(|key| key * 10).collect();
+        expected.extend([55, 1000]);
+        expected.sort();
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn test_modify_same_key() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        let keys = random_keys(11, 200);
+        let ids: Vec<HeapNodeId> = keys.iter().map(|&key| heap.push(key)).collect();
+        heap.pop();
+        heap.modify(ids[50], -1);
+        let parents = |heap: &FibonacciHeap<i32>| -> Vec<Option<HeapNodeId>> {
+            heap.nodes.iter().map(|node| node.as_ref().and_then(|node| node.parent)).collect()
+        };
+        let mut roots = heap.root_ids();
+        roots.sort();
+        let forest = parents(&heap);
+        let expected = heap.clone().into_sorted_vec();
+
+        let current: Vec<(HeapNodeId, i32)> = heap.iter().map(|(id, &key)| (id, key)).collect();
+        for (id, key) in current {
+            heap.modify(id, key);
+        }
+        let mut roots_after = heap.root_ids();
+        roots_after.sort();
+        assert_eq!(roots_after, roots);
+        assert_eq!(parents(&heap), forest);
+        assert_eq!(heap.min_key(), Some(&-1));
+        assert_eq!(heap.into_sorted_vec(), expected);
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        for key in random_keys(5, 1000) {
+            heap.push(key);
+        }
+        heap.pop();
+        let rank_capacity = heap.rank_id_cache.capacity();
+        while heap.pop().is_some() {}
+        heap.shrink_to_fit();
+        assert!(heap.rank_id_cache.capacity() < rank_capacity);
+        assert_eq!(heap.floating_ids.capacity(), 0);
+
+        // still usable, and the ids are not reused
+        let id = heap.push(3);
+        assert_eq!(id, 1000);
+        heap.push(1);
+        assert_eq!(heap.pop(), Some((1001, 1)));
+        assert_eq!(heap.pop(), Some((id, 3)));
+    }
+
+    #[test]
+    fn test_random_operations() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        let mut alive: Vec<HeapNodeId> = Vec::new();
+        let randoms = random_keys(2024, 6000);
+        for pair in randoms.chunks(2) {
+            let (operation, value) = (pair[0], pair[1]);
+            match operation % 10 {
+                0..=3 => alive.push(heap.push(value)),
+                4..=6 if !alive.is_empty() => {
+                    // both directions
+                    let id = alive[value as usize % alive.len()];
+                    heap.modify(id, value - 500);
+                }
+                _ => {
+                    if let Some((id, _)) = heap.pop() {
+                        alive.retain(|&alive_id| alive_id != id);
+                    }
+                }
+            }
+            assert!(heap.validate());
+            assert_eq!(heap.len(), alive.len());
+        }
+    }
+
+    /// an operation of the property test. ids are encoded as an index into the nodes alive at that time,
+    /// so that any subsequence of the operations is still valid (which is what shrinking needs).
+    #[derive(Debug, Clone, Copy)]
+    enum Operation {
+        Push(i32),
+        Pop,
+        Modify(usize, i32),
+    }
+
+    fn random_operations(seed: u64, len: usize) -> Vec<Operation> {
+        random_keys(seed, len * 2)
+            .chunks(2)
+            .map(|pair| match pair[0] % 3 {
+                0 => Operation::Push(pair[1]),
+                1 => Operation::Pop,
+                _ => Operation::Modify(pair[0] as usize, pair[1] - 500),
+            })
+            .collect()
+    }
+
+    /// apply the operations to the heap and to a reference model (keys by id), and compare every pop
+    fn check_against_model(operations: &[Operation]) -> Result<(), String> {
+        let mut heap = FibonacciHeap::<i32>::new();
+        let mut model = BTreeMap::<HeapNodeId, i32>::new();
+        for (step, &operation) in operations.iter().enumerate() {
+            match operation {
+                Operation::Push(key) => {
+                    model.insert(heap.push(key), key);
+                }
+                Operation::Modify(_, _) if model.is_empty() => {}
+                Operation::Modify(index, key) => {
+                    let id = *model.keys().nth(index % model.len()).unwrap();
+                    heap.modify(id, key);
+                    model.insert(id, key);
+                }
+                Operation::Pop => {
+                    let expected = model.values().min().copied();
+                    let popped = heap.pop();
+                    if popped.map(|(_, key)| key) != expected {
+                        return Err(format!("step {}: popped {:?}, expected {:?}", step, popped, expected));
+                    }
+                    if let Some((id, key)) = popped {
+                        if model.remove(&id) != Some(key) {
+                            return Err(format!("step {}: popped {:?} which is not in the model", step, popped));
+                        }
+                    }
+                }
+            }
+            if heap.len() != model.len() {
+                return Err(format!("step {}: len {}, expected {}", step, heap.len(), model.len()));
+            }
+        }
+        Ok(())
+    }
+
+    /// remove operations one by one as long as it still fails, to report a minimal failing sequence
+    fn shrink(mut operations: Vec<Operation>) -> Vec<Operation> {
+        let mut index = 0;
+        while index < operations.len() {
+            let mut candidate = operations.clone();
+            candidate.remove(index);
+            if check_against_model(&candidate).is_err() {
+                operations = candidate;
+            } else {
+                index += 1;
+            }
+        }
+        operations
+    }
+
+    #[test]
+    fn test_against_model() {
+        for seed in 1..=50 {
+            let operations = random_operations(seed, 400);
+            if let Err(error) = check_against_model(&operations) {
+                let minimal = shrink(operations);
+                panic!(
+                    "seed {}: {}\nminimal failing operations: {:?}\n{:?}",
+                    seed,
+                    error,
+                    minimal,
+                    check_against_model(&minimal)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        assert!(heap.is_empty());
+        let ids: Vec<HeapNodeId> = [4, 1, 7, 3, 9].iter().map(|&key| heap.push(key)).collect();
+        assert_eq!(heap.iter().count(), heap.len());
+        assert_eq!(heap.len(), 5);
+
+        heap.pop();
+        heap.modify(ids[2], 0);
+        assert_eq!(heap.len(), 4);
+        assert_eq!(heap.iter().count(), heap.len());
+        // the popped one is not in the heap
+        assert!(heap.iter().all(|(id, _)| id != ids[1]));
+        assert!(heap.iter().any(|(id, &key)| id == ids[2] && key == 0));
+    }
+
+    #[test]
+    fn test_root_ids() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        let keys = random_keys(3, 1000);
+        for &key in keys.iter() {
+            heap.push(key);
+        }
+        // not consolidated yet
+        assert_eq!(heap.tree_count(), 1000);
+        heap.pop();
+        // ranks of the roots are distinct, and a tree of rank r has at least 2^r nodes
+        assert!(heap.tree_count() <= 10);
+        assert_eq!(heap.root_ids().len(), heap.tree_count());
+        let mut ranks: Vec<usize> = heap
+            .root_ids()
+            .iter()
+            .map(|&id| heap.nodes[id].as_ref().unwrap().rank())
+            .collect();
+        ranks.sort();
+        ranks.dedup();
+        assert_eq!(ranks.len(), heap.tree_count());
+    }
+
+    #[test]
+    fn test_node_parent_and_children() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        let ids: Vec<HeapNodeId> = (0..9).map(|key| heap.push(key)).collect();
+        // consolidated into trees of rank 3 (8 nodes)
+        assert_eq!(heap.pop(), Some((ids[0], 0)));
+        assert_eq!(heap.root_ids(), vec![ids[1]]);
+        assert_eq!(heap.node_parent(ids[1]), None);
+        assert_eq!(heap.node_children(ids[1]).len(), 3);
+
+        let mut visited = 0;
+        let mut stack = heap.root_ids();
+        while let Some(id) = stack.pop() {
+            visited += 1;
+            for child in heap.node_children(id) {
+                assert_eq!(heap.node_parent(child), Some(id));
+                stack.push(child);
+            }
+        }
+        assert_eq!(visited, heap.len());
+        // a leaf, and a popped node
+        assert!(heap.node_children(ids[8]).is_empty());
+        assert_eq!(heap.node_parent(ids[0]), None);
+        assert!(heap.node_children(ids[0]).is_empty());
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        let ids: Vec<HeapNodeId> = [8, 3, 5, 1, 9, 4, 7].iter().map(|&key| heap.push(key)).collect();
+        heap.pop();
+        heap.modify(ids[4], 2);
+
+        let mut clone = heap.clone();
+        // tracked ids are valid for the clone
+        clone.modify(ids[0], 0);
+        assert_eq!(clone.pop(), Some((ids[0], 0)));
+        assert_eq!(clone.pop(), Some((ids[4], 2)));
+
+        // the original is untouched
+        assert_eq!(heap.pop(), Some((ids[4], 2)));
+        assert_eq!(heap.pop(), Some((ids[1], 3)));
+        let rest: Vec<i32> = std::iter::from_fn(|| heap.pop().map(|(_, key)| key)).collect();
+        assert_eq!(rest, vec![4, 5, 7, 8]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        let ids: Vec<HeapNodeId> = [8, 3, 5, 1, 9, 4, 7].iter().map(|&key| heap.push(key)).collect();
+        heap.pop();
+        heap.modify(ids[4], 2);
+
+        let json = serde_json::to_string(&heap).unwrap();
+        let mut restored: FibonacciHeap<i32> = serde_json::from_str(&json).unwrap();
+        // ids are still valid
+        heap.modify(ids[0], 0);
+        restored.modify(ids[0], 0);
+        while let Some(popped) = heap.pop() {
+            assert_eq!(restored.pop(), Some(popped));
+        }
+        assert!(restored.pop().is_none());
+    }
 }
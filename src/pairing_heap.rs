@@ -0,0 +1,294 @@
+use std::cmp::Ordering;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::mutable_heap::{HeapError, MutableHeap};
+
+type HeapNodeId = usize;
+
+// a single multi-way tree. children of a node are kept as a doubly linked list:
+// `child` is the leftmost child, and `sibling` / `prev` link the children of the same parent.
+// `prev` of the leftmost child is its parent, so that any node but the root can be detached in O(1).
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Node<K> {
+    /// primitive data
+    key: K,
+    child: Option<HeapNodeId>,
+    sibling: Option<HeapNodeId>,
+    prev: Option<HeapNodeId>,
+}
+
+/// pairing heap. simpler than the fibonacci heap, and often faster in practice.
+/// ids are never reused in the same manner as `FibonacciHeap`.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PairingHeap<K> {
+    /// arena of the nodes indexed by their id. popped nodes are left as `None`.
+    nodes: Vec<Option<Node<K>>>,
+    root: Option<HeapNodeId>,
+    /// number of the nodes in the heap
+    len: usize,
+}
+
+impl<K: Ord> Default for PairingHeap<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord> PairingHeap<K> {
+    /// ids are never reused, so the arena grows by one slot on every push as in `FibonacciHeap::new`.
+    /// replace the heap once it is empty to release them.
+    pub fn new() -> Self {
+        PairingHeap {
+            nodes: Vec::new(),
+            root: None,
+            len: 0,
+        }
+    }
+    fn node(&self, id: HeapNodeId) -> &Node<K> {
+        self.nodes[id].as_ref().unwrap()
+    }
+    fn node_mut(&mut self, id: HeapNodeId) -> &mut Node<K> {
+        self.nodes[id].as_mut().unwrap()
+    }
+    /// returns true if the node with the id is in the heap
+    fn contains(&self, id: HeapNodeId) -> bool {
+        self.nodes.get(id).is_some_and(Option::is_some)
+    }
+    /// take the ids of two tree heads and join them: the larger one becomes the leftmost child.
+    /// returns id of head of the merged tree
+    fn link(&mut self, tree1: HeapNodeId, tree2: HeapNodeId) -> HeapNodeId {
+        debug_assert!(tree1 != tree2);
+        debug_assert!(self.node(tree1).prev.is_none() && self.node(tree1).sibling.is_none());
+        debug_assert!(self.node(tree2).prev.is_none() && self.node(tree2).sibling.is_none());
+
+        // BOUNDARY: on a tie, tree1 stays the parent
+        let (parent, child) = if self.node(tree2).key < self.node(tree1).key {
+            (tree2, tree1)
+        } else {
+            (tree1, tree2)
+        };
+        let former_child = self.node(parent).child;
+        if let Some(former_child) = former_child {
+            self.node_mut(former_child).prev = Some(child);
+        }
+        let child_node = self.node_mut(child);
+        child_node.sibling = former_child;
+        child_node.prev = Some(parent);
+        self.node_mut(parent).child = Some(child);
+        parent
+    }
+    /// link the tree with the root (or make it the root if the heap is empty)
+    fn meld_into_root(&mut self, tree: HeapNodeId) {
+        self.root = Some(match self.root {
+            Some(root) => self.link(root, tree),
+            None => tree,
+        });
+    }
+    /// cut off the subtree of the node from its parent. the node must not be the root.
+    fn detach(&mut self, id: HeapNodeId) {
+        let node = self.node_mut(id);
+        let prev = node.prev.take().unwrap();
+        let sibling = node.sibling.take();
+        if let Some(sibling) = sibling {
+            self.node_mut(sibling).prev = Some(prev);
+        }
+        let prev_node = self.node_mut(prev);
+        if prev_node.child == Some(id) {
+            prev_node.child = sibling;
+        } else {
+            prev_node.sibling = sibling;
+        }
+    }
+    /// cut off all the children of the node and join them into one tree by the two-pass merge:
+    /// link them in pairs from left to right, then link the pairs from right to left.
+    /// returns id of head of the joined tree, `None` if the node has no children.
+    fn merge_children(&mut self, id: HeapNodeId) -> Option<HeapNodeId> {
+        let mut children = Vec::new();
+        let mut next = self.node_mut(id).child.take();
+        while let Some(child_id) = next {
+            let child = self.node_mut(child_id);
+            next = child.sibling.take();
+            child.prev = None;
+            children.push(child_id);
+        }
+
+        // first pass
+        let pairs: Vec<HeapNodeId> = children
+            .chunks(2)
+            .map(|pair| match *pair {
+                [tree1, tree2] => self.link(tree1, tree2),
+                [tree] => tree,
+                _ => unreachable!(),
+            })
+            .collect();
+        // second pass
+        pairs.into_iter().rev().reduce(|merged, tree| self.link(tree, merged))
+    }
+    /// move the node towards the front. the subtree of the node does not violate the heap property,
+    /// so it is just cut off and linked with the root. O(1), amortized O(log n) conjectured.
+    pub fn decrease_key(&mut self, id: HeapNodeId, new_key: K) -> Result<(), HeapError> {
+        let node = self.nodes.get_mut(id).and_then(Option::as_mut);
+        let node = node.ok_or(HeapError::NotFound(id))?;
+        if new_key >= node.key {
+            return Err(HeapError::WrongDirection(id));
+        }
+        node.key = new_key;
+        if self.root != Some(id) {
+            self.detach(id);
+            self.meld_into_root(id);
+        }
+        Ok(())
+    }
+    /// move the node towards the back. the children may be smaller than the new key,
+    /// so they are merged into a tree apart from the node, and both are linked with the root again.
+    /// amortized O(log n).
+    pub fn increase_key(&mut self, id: HeapNodeId, new_key: K) -> Result<(), HeapError> {
+        let node = self.nodes.get_mut(id).and_then(Option::as_mut);
+        let node = node.ok_or(HeapError::NotFound(id))?;
+        if new_key <= node.key {
+            return Err(HeapError::WrongDirection(id));
+        }
+        node.key = new_key;
+        if self.root == Some(id) {
+            self.root = None;
+        } else {
+            self.detach(id);
+        }
+        if let Some(children) = self.merge_children(id) {
+            self.meld_into_root(children);
+        }
+        self.meld_into_root(id);
+        Ok(())
+    }
+}
+
+impl<K: Ord> MutableHeap<K> for PairingHeap<K> {
+    fn push(&mut self, key: K) -> HeapNodeId {
+        let id = self.nodes.len();
+        self.nodes.push(Some(Node {
+            key,
+            child: None,
+            sibling: None,
+            prev: None,
+        }));
+        self.len += 1;
+        self.meld_into_root(id);
+        id
+    }
+    fn pop(&mut self) -> Option<(HeapNodeId, K)> {
+        let root = self.root.take()?;
+        self.root = self.merge_children(root);
+        self.len -= 1;
+        match self.nodes[root].take() {
+            Some(root_node) => Some((root, root_node.key)),
+            None => panic!("root node is unexpectedly removed in a way"),
+        }
+    }
+    fn get_min(&self) -> Option<HeapNodeId> {
+        self.root
+    }
+    fn modify(&mut self, id: HeapNodeId, new_key: K) {
+        // if client not tracks the id properly, they may try to modify a non-existing node
+        assert!(self.contains(id));
+
+        let node = self.node_mut(id);
+        let result = match new_key.cmp(&node.key) {
+            Ordering::Less => self.decrease_key(id, new_key),
+            Ordering::Greater => self.increase_key(id, new_key),
+            Ordering::Equal => {
+                // the structure does not change
+                node.key = new_key;
+                Ok(())
+            }
+        };
+        debug_assert!(result.is_ok());
+    }
+}
+
+/// utilities
+impl<K: Ord> PairingHeap<K> {
+    /// get the minimum `key` itself. O(1).
+    pub fn min_key(&self) -> Option<&K> {
+        self.root.map(|root| &self.node(root).key)
+    }
+    /// number of the nodes in the heap. O(1).
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// pop all the nodes in order. O(n log n).
+    pub fn into_sorted_vec(mut self) -> Vec<(HeapNodeId, K)> {
+        std::iter::from_fn(|| self.pop()).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pairing_heap() {
+        let mut heap = PairingHeap::<i32>::new();
+        for key in [1, 2, 2, 3] {
+            heap.push(key);
+        }
+        let five = heap.push(5);
+        let eight = heap.push(8);
+        heap.push(13);
+        heap.push(21);
+        let thirty_four = heap.push(34);
+        heap.push(4);
+        let ten = heap.push(10);
+        heap.push(11);
+        heap.push(3);
+
+        heap.modify(five, -1);
+        assert_eq!(heap.pop(), Some((five, -1)));
+        let (_, key) = heap.pop().unwrap();
+        assert_eq!(key, 1);
+
+        heap.modify(thirty_four, -1);
+        heap.modify(ten, -1);
+        heap.modify(eight, 50);
+
+        let mut previous_key = i32::MIN;
+        while let Some((_, key)) = heap.pop() {
+            assert!(previous_key <= key);
+            previous_key = key;
+        }
+        assert_eq!(previous_key, 50);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_decrease_and_increase_key() {
+        let mut heap = PairingHeap::<i32>::new();
+        let ids: Vec<HeapNodeId> = (0..20).map(|key| heap.push(key * 10)).collect();
+        // make a deeper tree
+        assert_eq!(heap.pop(), Some((ids[0], 0)));
+
+        assert_eq!(heap.decrease_key(ids[15], 5), Ok(()));
+        assert_eq!(heap.min_key(), Some(&5));
+        assert_eq!(heap.increase_key(ids[15], 1000), Ok(()));
+        assert_eq!(heap.min_key(), Some(&10));
+        assert_eq!(heap.increase_key(ids[1], 55), Ok(()));
+        assert_eq!(heap.min_key(), Some(&20));
+
+        assert_eq!(heap.decrease_key(ids[2], 20), Err(HeapError::WrongDirection(ids[2])));
+        assert_eq!(heap.increase_key(ids[2], 10), Err(HeapError::WrongDirection(ids[2])));
+        assert_eq!(heap.decrease_key(ids[0], -1), Err(HeapError::NotFound(ids[0])));
+
+        let keys: Vec<i32> = heap.into_sorted_vec().into_iter().map(|(_, key)| key).collect();
+        let mut expected: Vec<i32> = (2..20).filter(|&key| key != 15).map(|key| key * 10).collect();
+        expected.extend([55, 1000]);
+        expected.sort();
+        assert_eq!(keys, expected);
+    }
+}
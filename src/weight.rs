@@ -0,0 +1,145 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Result};
+use std::ops::Add;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// type of arc weights and node distances.
+/// `INFINITY` is the distance of nodes which are not reached (yet). it is reserved as a sentinel:
+/// it must be larger than any distance, and nothing is ever added to it.
+/// signed weights are fine, so negative (e.g. reweighted) distances never collide with it.
+pub trait Weight: Copy + Ord + Add<Output = Self> {
+    const ZERO: Self;
+    const INFINITY: Self;
+    /// `None` for `INFINITY`, the distance itself otherwise
+    fn finite(self) -> Option<Self> {
+        (self != Self::INFINITY).then_some(self)
+    }
+    /// `None` if the sum overflows or reaches `INFINITY`, which would make a reached node look unreached
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_weight_for_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl Weight for $integer {
+                const ZERO: Self = 0;
+                const INFINITY: Self = <$integer>::MAX;
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$integer>::checked_add(self, other).and_then(Weight::finite)
+                }
+            }
+        )*
+    };
+}
+
+impl_weight_for_integer!(usize, u64, u32, i64, i32);
+
+/// the weight is NaN or infinite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonFiniteWeight;
+impl Display for NonFiniteWeight {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "weight is not finite")
+    }
+}
+impl std::error::Error for NonFiniteWeight {}
+
+/// `f64` weight, totally ordered by `f64::total_cmp` so that it can be a heap key.
+/// `NaN` and infinities are rejected on construction: `+inf` is the `INFINITY` sentinel, and `-inf` would make
+/// a sum NaN. so the order agrees with the usual one on every value it can hold (except that `-0.0 < 0.0`).
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OrderedWeight(f64);
+impl OrderedWeight {
+    pub fn new(value: f64) -> std::result::Result<Self, NonFiniteWeight> {
+        if !value.is_finite() {
+            return Err(NonFiniteWeight);
+        }
+        Ok(OrderedWeight(value))
+    }
+    pub fn zero() -> Self {
+        Self::ZERO
+    }
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+impl PartialEq for OrderedWeight {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for OrderedWeight {}
+impl PartialOrd for OrderedWeight {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for OrderedWeight {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+impl Add for OrderedWeight {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        OrderedWeight(self.0 + other.0)
+    }
+}
+impl Display for OrderedWeight {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl Weight for OrderedWeight {
+    const ZERO: Self = OrderedWeight(0.0);
+    const INFINITY: Self = OrderedWeight(f64::INFINITY);
+    fn checked_add(self, other: Self) -> Option<Self> {
+        let sum = self.0 + other.0;
+        sum.is_finite().then_some(OrderedWeight(sum))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ordered_weight() {
+        assert_eq!(OrderedWeight::new(f64::NAN), Err(NonFiniteWeight));
+        assert_eq!(OrderedWeight::new(f64::INFINITY), Err(NonFiniteWeight));
+        assert_eq!(OrderedWeight::new(f64::NEG_INFINITY), Err(NonFiniteWeight));
+        assert!(OrderedWeight::new(f64::MAX).is_ok());
+        let half = OrderedWeight::new(0.5).unwrap();
+        let quarter = OrderedWeight::new(0.25).unwrap();
+        assert!(quarter < half);
+        assert_eq!((quarter + quarter), half);
+        assert_eq!(OrderedWeight::zero().value(), 0.0);
+        assert!(half < OrderedWeight::INFINITY);
+        assert_eq!(OrderedWeight::INFINITY.finite(), None);
+        assert_eq!(half.finite(), Some(half));
+    }
+
+    #[test]
+    fn test_finite() {
+        assert_eq!(i64::INFINITY.finite(), None);
+        assert_eq!((-5i64).finite(), Some(-5));
+        assert_eq!(i64::MIN.finite(), Some(i64::MIN));
+        assert_eq!(usize::ZERO.finite(), Some(0));
+    }
+
+    #[test]
+    fn test_checked_add() {
+        assert_eq!(Weight::checked_add(2usize, 3), Some(5));
+        assert_eq!(Weight::checked_add(usize::MAX - 1, 1), None);
+        assert_eq!(Weight::checked_add(usize::MAX - 1, 2), None);
+        assert_eq!(Weight::checked_add(i64::MIN, -1), None);
+        assert_eq!(Weight::checked_add(i64::MIN, 1), Some(i64::MIN + 1));
+        let max = OrderedWeight::new(f64::MAX).unwrap();
+        let one = OrderedWeight::new(1.0).unwrap();
+        assert_eq!(one.checked_add(one), OrderedWeight::new(2.0).ok());
+        assert_eq!(max.checked_add(max), None);
+    }
+}